@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+
+use crate::{
+    config::{cache_path, load_json, write_private_json},
+    http::{Endpoints, Method, Request, Transport},
+    token, Credentials, Warning,
+};
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct AppAccess {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: u64,
+    pub token_type: String,
+
+    /// When the token expires, as a unix timestamp
+    #[serde(default)]
+    pub expires_at: i64,
+
+    /// The client id this token was issued for
+    #[serde(default)]
+    pub client_id: String,
+
+    #[serde(skip)]
+    client_secret: String,
+}
+
+impl AppAccess {
+    pub fn get_cache_path() -> Option<PathBuf> {
+        cache_path("token_cache.json")
+    }
+
+    /// Gets an app access token, reusing the cached one if it is still valid
+    ///
+    /// A new token is written to the cache, if that fails the token is still usable and a warning is returned
+    pub fn get(
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+        credentials: &Credentials,
+    ) -> anyhow::Result<(Self, Option<Warning>)> {
+        if let Some(mut access) = Self::load_cache()
+            .filter(|access| access.client_id == credentials.client_id)
+            .filter(|access| !access.is_expired())
+        {
+            if access.validate(transport, endpoints) {
+                access.client_secret = credentials.client_secret.clone();
+                return Ok((access, None));
+            }
+        }
+
+        let access = Self::fetch(transport, endpoints, credentials)?;
+        let warning = access.sync().err().map(|err| Warning::cache("token", &err));
+        Ok((access, warning))
+    }
+
+    /// Fetches a new token, replacing this one
+    ///
+    /// Like [`AppAccess::get`], a warning is returned if the new token cannot be cached
+    pub fn renew(
+        &mut self,
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+    ) -> anyhow::Result<Option<Warning>> {
+        let credentials = Credentials {
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+        };
+        *self = Self::fetch(transport, endpoints, &credentials)?;
+        Ok(self.sync().err().map(|err| Warning::cache("token", &err)))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        token::is_expired(self.expires_at)
+    }
+
+    fn fetch(
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+        credentials: &Credentials,
+    ) -> anyhow::Result<Self> {
+        let access: Self = transport
+            .send(Request {
+                method: Method::Post,
+                url: &endpoints.auth("token"),
+                query: &[
+                    ("client_id", &credentials.client_id),
+                    ("client_secret", &credentials.client_secret),
+                    ("grant_type", "client_credentials"),
+                ],
+                headers: &[],
+            })?
+            .error_for_status()?
+            .json()?;
+
+        Ok(Self {
+            expires_at: chrono::Utc::now().timestamp() + access.expires_in as i64,
+            client_id: credentials.client_id.clone(),
+            client_secret: credentials.client_secret.clone(),
+            ..access
+        })
+    }
+
+    fn validate(&mut self, transport: &dyn Transport, endpoints: &Endpoints) -> bool {
+        let validation =
+            match token::validate(transport, endpoints, &self.access_token, &self.client_id) {
+                Some(validation) => validation,
+                None => return false,
+            };
+
+        self.expires_in = validation.expires_in;
+        self.expires_at = validation.expires_at();
+        !self.is_expired()
+    }
+
+    fn load_cache() -> Option<Self> {
+        load_json(Self::get_cache_path())
+    }
+
+    fn sync(&self) -> anyhow::Result<()> {
+        let path = Self::get_cache_path().with_context(|| "cannot get the cache path")?;
+        // the token is a secret
+        write_private_json(&path, self)
+    }
+}
@@ -1,19 +1,42 @@
 use anyhow::Context as _;
 
-use crate::{WHAT_STREAM_CLIENT_ID, WHAT_STREAM_CLIENT_SECRET};
+use crate::{stream::Limit, Credentials, Filter};
 
 #[derive(Debug)]
 pub struct Args {
+    pub command: Option<Command>,
     pub sort: Option<SortAction>,
+    pub limit: Limit,
+    pub filter: Filter,
+    pub followers: bool,
+    pub show_offline: bool,
+    pub following: bool,
+    pub everywhere: bool,
+    pub fuzzy: bool,
+    pub configured: bool,
     pub query: Vec<String>,
     pub languages: Vec<String>,
+    pub categories: Vec<String>,
     pub json: bool,
+    pub strict: bool,
     pub demo: bool,
 }
 
 impl Args {
     pub fn parse() -> anyhow::Result<Self> {
-        let mut args = pico_args::Arguments::from_env();
+        let mut args = std::env::args_os().skip(1).collect::<Vec<_>>();
+
+        // everything after `--` is part of the query, e.g. `-- top` searches for `top` instead of running it
+        let literal = match args.iter().position(|arg| arg == "--") {
+            Some(n) => {
+                let literal = args.split_off(n + 1);
+                args.pop();
+                literal
+            }
+            None => vec![],
+        };
+
+        let mut args = pico_args::Arguments::from_vec(args);
 
         if args.contains("-h") {
             Self::print_short_help();
@@ -35,6 +58,16 @@ impl Args {
             std::process::exit(0)
         }
 
+        if args.contains("--print-credentials-path") {
+            println!(
+                "{}",
+                Credentials::get_credentials_path()
+                    .with_context(|| "your system does not have a configuration directory")?
+                    .to_string_lossy()
+            );
+            std::process::exit(0)
+        }
+
         if args.contains("--print-config-path") {
             println!(
                 "{}",
@@ -45,28 +78,87 @@ impl Args {
             std::process::exit(0)
         }
 
+        // anything that isn't a known subcommand is part of the query
+        let (command, head) = match args.subcommand()? {
+            Some(cmd) => match &*cmd {
+                "categories" => (Some(Command::Categories), None),
+                "schedule" => (Some(Command::Schedule), None),
+                "top" => (Some(Command::Top), None),
+                "channel" => (Some(Command::Channel), None),
+                "login" => (Some(Command::Login), None),
+                "logout" => (Some(Command::Logout), None),
+                _ => (None, Some(cmd)),
+            },
+            None => (None, None),
+        };
+
         let demo = args.contains("--demo");
 
         let json = args.contains(["-j", "--json"]);
+        let strict = args.contains("--strict");
         let sort = args.opt_value_from_str(["-s", "--sort"])?;
 
+        let limit = Limit {
+            total: args.opt_value_from_str(["-n", "--limit"])?,
+            per_query: args.opt_value_from_str("--limit-per-query")?,
+            max_pages: args.opt_value_from_str("--max-pages")?,
+        };
+
+        let followers = args.contains("--followers");
+        let show_offline = args.contains("--show-offline");
+        let following = args.contains("--following");
+        let everywhere = args.contains("--everywhere");
+        let fuzzy = args.contains("--fuzzy");
+        let configured = args.contains("--configured");
+        let filter = Filter {
+            min_followers: args.opt_value_from_str("--min-followers")?,
+            max_followers: args.opt_value_from_str("--max-followers")?,
+            hide_mature: args.contains("--hide-mature"),
+            hide_labels: args.values_from_str("--hide-label")?,
+            live_only: args.contains("--live-only"),
+        };
+
         let languages: Vec<String> = args.values_from_str(["-l", "--language"])?;
+        let categories: Vec<String> = args.values_from_str(["-c", "--category"])?;
 
-        let query = args
-            .finish()
+        let query = head
             .into_iter()
-            .map(|s| s.to_string_lossy().to_string())
+            .chain(
+                args.finish()
+                    .into_iter()
+                    .chain(literal)
+                    .map(|s| s.to_string_lossy().to_string()),
+            )
             .collect::<Vec<_>>();
 
         Ok(Self {
+            command,
             sort,
+            limit,
+            filter,
+            followers,
+            show_offline,
+            following,
+            everywhere,
+            fuzzy,
+            configured,
             query,
             languages,
+            categories,
             json,
+            strict,
             demo,
         })
     }
 
+    /// Whether the follower counts are needed, because they were asked for or are sorted or filtered by
+    pub fn needs_followers(&self) -> bool {
+        self.followers
+            || self.filter.min_followers.is_some()
+            || self.filter.max_followers.is_some()
+            || matches!(self.sort, Some(SortAction { column, .. }) if column == Column::Followers)
+    }
+
     fn print_short_help() {
         Self::print_version();
         println!();
@@ -83,6 +175,16 @@ impl Args {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Categories,
+    Schedule,
+    Top,
+    Channel,
+    Login,
+    Logout,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct SortAction {
     pub column: Column,
@@ -98,8 +200,9 @@ impl std::str::FromStr for SortAction {
             "viewers" => Column::Viewers,
             "uptime" => Column::Uptime,
             "name" => Column::Name,
+            "followers" => Column::Followers,
             name => anyhow::bail!(
-                "invalid column: {}. supported columns: [viewers | uptime | name]",
+                "invalid column: {}. supported columns: [viewers | uptime | name | followers]",
                 name
             ),
         };
@@ -123,6 +226,7 @@ pub enum Column {
     Viewers,
     Uptime,
     Name,
+    Followers,
 }
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq)]
@@ -130,23 +234,3 @@ pub enum Direction {
     Descending,
     Ascending,
 }
-
-#[derive(Debug, Clone, serde::Deserialize)]
-pub struct AppAccess {
-    pub access_token: String,
-    pub refresh_token: Option<String>,
-    pub expires_in: u64,
-    pub token_type: String,
-}
-
-impl AppAccess {
-    pub fn get() -> anyhow::Result<Self> {
-        ureq::post("https://id.twitch.tv/oauth2/token")
-            .query("client_id", WHAT_STREAM_CLIENT_ID)
-            .query("client_secret", WHAT_STREAM_CLIENT_SECRET)
-            .query("grant_type", "client_credentials")
-            .call()?
-            .into_json()
-            .map_err(Into::into)
-    }
-}
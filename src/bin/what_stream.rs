@@ -16,27 +16,311 @@ fn try_enable_colors() {
     }
 }
 
-fn render_streams<'a, I>(out: &mut dyn Write, config: &Config, streams: I) -> anyhow::Result<()>
-where
-    I: IntoIterator<Item = (String, &'a [Stream])>,
-{
-    let Appearance { glyphs, colors } = &config.appearance;
-
-    streams
-        .into_iter()
+fn entries<'a>(
+    config: &Config,
+    query: &'a str,
+    streams: &'a [Stream],
+    categories: bool,
+) -> Entries<'a> {
+    Entries {
+        query,
+        streams,
+        display_names: config.appearance.display_names,
+        badges: config.appearance.badges,
+        categories,
+    }
+}
+
+fn render_groups(
+    out: &mut dyn Write,
+    config: &Config,
+    groups: &[Box<dyn Render + '_>],
+) -> anyhow::Result<()> {
+    let Appearance { glyphs, colors, .. } = &config.appearance;
+    groups.iter().enumerate().try_for_each(|(n, group)| {
+        if n > 0 {
+            writeln!(out)?;
+        }
+        group.render(out, glyphs, colors)
+    })
+}
+
+fn connect(config: &Config, as_user: bool) -> anyhow::Result<Client> {
+    let credentials = Credentials::load(config)?;
+    let endpoints = http::Endpoints::load(config);
+    let transport = http::UreqTransport::default();
+
+    let user_access = as_user
+        .then(|| UserAccess::get(&transport, &endpoints, &credentials))
+        .transpose()?;
+
+    let mut client = Client::new(transport, endpoints, &credentials)?;
+    if let Some(user_access) = user_access {
+        client.set_user_access(user_access);
+    }
+    Ok(client)
+}
+
+fn get_user_access(config: &Config) -> anyhow::Result<UserAccess> {
+    let credentials = Credentials::load(config)?;
+    let endpoints = http::Endpoints::load(config);
+    UserAccess::get(&http::UreqTransport::default(), &endpoints, &credentials)
+}
+
+fn login(config: &Config) -> anyhow::Result<()> {
+    let credentials = Credentials::load(config)?;
+    let endpoints = http::Endpoints::load(config);
+
+    let user_access = UserAccess::login(
+        &http::UreqTransport::default(),
+        &endpoints,
+        &credentials,
+        |code| {
+            println!(
+                "to log in, go to {} and enter the code: {}",
+                code.verification_uri, code.user_code
+            );
+            println!("waiting for you to log in..");
+        },
+    )?;
+
+    println!("logged in as {}", user_access.login);
+    Ok(())
+}
+
+fn logout(config: &Config) -> anyhow::Result<()> {
+    let endpoints = http::Endpoints::load(config);
+    if UserAccess::logout(&http::UreqTransport::default(), &endpoints)? {
+        println!("logged out");
+    } else {
+        println!("you are not logged in");
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct Output<'a> {
+    streams: &'a HashMap<String, Vec<Stream>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    watchlist: Option<&'a Watched>,
+    excluded: &'a [Excluded],
+    warnings: &'a [Warning],
+}
+
+fn report_warnings(warnings: &[Warning]) {
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+fn report_retries(client: &Client) {
+    match client.retries() {
+        0 => {}
+        1 => eprintln!("note: 1 request to Twitch was retried"),
+        n => eprintln!("note: {} requests to Twitch were retried", n),
+    }
+}
+
+fn show_categories(args: &Args, config: &Config, client: &mut Client) -> anyhow::Result<()> {
+    let mut category_cache = CategoryCache::load_cache();
+    let categories = lookup_categories(&args.query, client, &mut category_cache)?;
+    report_retries(client);
+
+    let mut warnings = client.take_warnings();
+    if let Err(err) = category_cache.sync() {
+        warnings.push(Warning::cache("categories", &err));
+    }
+    report_warnings(&warnings);
+
+    if args.json {
+        let categories: HashMap<_, _> = categories.into_iter().collect();
+        println!("{}", serde_json::to_string_pretty(&categories)?);
+        return Ok(());
+    }
+
+    try_enable_colors();
+
+    let Appearance { glyphs, colors, .. } = &config.appearance;
+    let mut out = std::io::stdout().lock();
+    categories
+        .iter()
         .enumerate()
-        .try_for_each(|(n, (query, streams))| {
+        .try_for_each(|(n, (query, categories))| {
             if n > 0 {
                 writeln!(out)?;
             }
-            Entries {
-                query: &query,
-                streams,
-            }
-            .render(out, glyphs, colors)
+            Categories { query, categories }.render(&mut out, glyphs, colors)
         })
 }
 
+fn show_schedules(
+    args: &Args,
+    config: &Config,
+    client: &mut Client,
+    logins: &[String],
+) -> anyhow::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Output<'a> {
+        schedules: &'a [Schedule],
+        warnings: &'a [Warning],
+    }
+
+    let Schedules {
+        schedules,
+        mut warnings,
+    } = fetch_schedules(client, logins)?;
+    warnings.extend(client.take_warnings());
+    report_retries(client);
+    report_warnings(&warnings);
+
+    // in strict mode, any warning is an error
+    let code = if args.strict && !warnings.is_empty() {
+        2
+    } else {
+        0
+    };
+
+    if args.json {
+        let output = Output {
+            schedules: &schedules,
+            warnings: &warnings,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        std::process::exit(code)
+    }
+
+    try_enable_colors();
+
+    let groups = schedules
+        .iter()
+        .map(|schedule| Box::new(Upcoming { schedule }) as Box<dyn Render>)
+        .collect::<Vec<_>>();
+
+    let mut out = std::io::stdout().lock();
+    render_groups(&mut out, config, &groups)?;
+    out.flush()?;
+    std::process::exit(code)
+}
+
+// the biggest categories have thousands of streams, so only the first pages of them are counted by default
+const TOP_MAX_PAGES: usize = 10;
+
+// how many of the top categories are shown by default
+const TOP_CATEGORIES: usize = 10;
+
+fn show_top(args: &Args, config: &Config, client: &mut Client) -> anyhow::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Output<'a> {
+        categories: &'a [CategoryStats],
+        warnings: &'a [Warning],
+    }
+
+    let mut languages = args.languages.clone();
+    append_maybe(&mut languages, &config.parameters.languages, |s| {
+        !s.is_empty()
+    });
+
+    let mut category_cache = CategoryCache::load_cache();
+    let mut warnings = vec![];
+
+    let configured = if !args.categories.is_empty() {
+        Some(args.categories.clone())
+    } else if args.configured {
+        let mut categories = config.parameters.categories.clone();
+        append_maybe(
+            &mut categories,
+            &DEFAULT_CATEGORIES.map(String::from),
+            |s| !s.is_empty(),
+        );
+        Some(categories)
+    } else {
+        None
+    };
+
+    let (query, categories, max_pages) = match &configured {
+        Some(categories) => {
+            let ids = resolve_categories(client, categories, &mut category_cache)?;
+            if let Err(err) = category_cache.sync() {
+                warnings.push(Warning::cache("categories", &err));
+            }
+            let categories = get_categories(client, &ids)?;
+            ("categories", categories, args.limit.max_pages)
+        }
+        None => {
+            let count = args.limit.total.unwrap_or(TOP_CATEGORIES);
+            let categories = get_top_categories(client, count)?;
+            let max_pages = args
+                .limit
+                .max_pages
+                .or(config.parameters.max_pages)
+                .or(Some(TOP_MAX_PAGES));
+            ("top categories", categories, max_pages)
+        }
+    };
+
+    let top = count_categories(client, &categories, &languages, max_pages)?;
+    warnings.extend(top.warnings);
+    warnings.extend(client.take_warnings());
+
+    report_retries(client);
+    report_warnings(&warnings);
+
+    // in strict mode, any warning is an error
+    let code = if args.strict && !warnings.is_empty() {
+        2
+    } else {
+        0
+    };
+
+    if args.json {
+        let output = Output {
+            categories: &top.categories,
+            warnings: &warnings,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        std::process::exit(code)
+    }
+
+    try_enable_colors();
+
+    let groups = [Box::new(Popularity {
+        query,
+        categories: &top.categories,
+    }) as Box<dyn Render>];
+
+    let mut out = std::io::stdout().lock();
+    render_groups(&mut out, config, &groups)?;
+    out.flush()?;
+    std::process::exit(code)
+}
+
+fn show_channel(
+    args: &Args,
+    config: &Config,
+    client: &mut Client,
+    login: &str,
+) -> anyhow::Result<()> {
+    let details = get_channel_details(client, login)?;
+    report_retries(client);
+    report_warnings(&client.take_warnings());
+
+    // so this can be used to check whether a channel is live
+    let code = if details.is_live() { 0 } else { 1 };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        std::process::exit(code)
+    }
+
+    try_enable_colors();
+
+    let Appearance { glyphs, colors, .. } = &config.appearance;
+    let mut out = std::io::stdout().lock();
+    Details { details: &details }.render(&mut out, glyphs, colors)?;
+    out.flush()?;
+    std::process::exit(code)
+}
+
 fn append_maybe<T: Clone>(left: &mut Vec<T>, right: &[T], retain: fn(&T) -> bool) {
     if left.is_empty() {
         left.extend(right.iter().cloned());
@@ -49,12 +333,28 @@ fn show_demo(config: &Config) -> anyhow::Result<()> {
     let out = std::io::stdout();
     let mut out = out.lock();
 
-    let Appearance { glyphs, colors } = &config.appearance;
+    let Appearance { glyphs, colors, .. } = &config.appearance;
     what_stream::Demo::show_off(&mut out, glyphs, colors)?;
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
+    // `channel` can be used to check whether a channel is live, where 1 means that it's offline.
+    // so any error on that path, even a bad flag or configuration, exits with 2 instead
+    if std::env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == "channel")
+    {
+        if let Err(err) = run() {
+            eprintln!("Error: {:?}", err);
+            std::process::exit(2)
+        }
+        return Ok(());
+    }
+    run()
+}
+
+fn run() -> anyhow::Result<()> {
     let mut args = Args::parse()?;
     // TODO this should probably notify the user that the configuration path doesn't exist
     // and prompt them to either make it, or maybe we should make it for them
@@ -65,56 +365,216 @@ fn main() -> anyhow::Result<()> {
         .transpose()?
         .unwrap_or_default();
 
+    match args.command {
+        Some(Command::Categories) => {
+            if args.query.is_empty() {
+                eprintln!("please provide a category to look up");
+                std::process::exit(1)
+            }
+            let mut client = connect(&config, false)?;
+            return show_categories(&args, &config, &mut client);
+        }
+        Some(Command::Schedule) => {
+            // without any logins, use the watchlist
+            let logins = if args.query.is_empty() {
+                &config.watchlist.logins
+            } else {
+                &args.query
+            };
+            if logins.is_empty() {
+                eprintln!("please provide the channels to show the schedules of, or add them to the watchlist");
+                std::process::exit(1)
+            }
+            let mut client = connect(&config, false)?;
+            return show_schedules(&args, &config, &mut client, logins);
+        }
+        Some(Command::Top) => {
+            let mut client = connect(&config, false)?;
+            return show_top(&args, &config, &mut client);
+        }
+        Some(Command::Channel) => {
+            let login = match &*args.query {
+                [login] => login,
+                _ => {
+                    eprintln!("please provide the channel to show");
+                    std::process::exit(2)
+                }
+            };
+            let mut client = connect(&config, false)?;
+            return show_channel(&args, &config, &mut client, login);
+        }
+        Some(Command::Login) => return login(&config),
+        Some(Command::Logout) => return logout(&config),
+        None => {}
+    }
+
     // TODO this is ugly
-    append_maybe(&mut args.languages, &*config.parameters.languages, |s| {
+    append_maybe(&mut args.languages, &config.parameters.languages, |s| {
         !s.is_empty()
     });
-    append_maybe(&mut args.query, &*config.parameters.query, |s| {
+    append_maybe(&mut args.query, &config.parameters.query, |s| !s.is_empty());
+    append_maybe(&mut args.categories, &config.parameters.categories, |s| {
         !s.is_empty()
     });
+    if args.categories.is_empty() {
+        args.categories = DEFAULT_CATEGORIES.iter().map(ToString::to_string).collect();
+    }
+    args.limit.max_pages = args.limit.max_pages.or(config.parameters.max_pages);
+    args.followers |= config.parameters.followers;
+    args.fuzzy |= config.parameters.fuzzy;
+    args.filter.hide_mature |= config.parameters.hide_mature;
+    args.filter.live_only |= config.parameters.live_only;
+    args.filter
+        .hide_labels
+        .extend(config.parameters.hide_labels.iter().cloned());
 
     if args.demo {
         show_demo(&config)?;
         std::process::exit(0);
     }
 
-    if args.query.is_empty() {
+    if args.query.is_empty() && config.watchlist.logins.is_empty() {
         eprintln!("please provide something to filter by");
         std::process::exit(1)
     }
 
+    let query = Query::parse_args(&args.query, &config.aliases)?;
+
+    let mut followers = args.needs_followers();
+    let mut warnings = vec![];
+
     // TODO read from the config to see if we should override the token?
-    let app_access = AppAccess::get()?;
+    let mut client = connect(&config, args.following)?;
+
+    // the follower counts are only given to logged in users, the streams are still shown without them
+    if followers && client.user_access().is_none() {
+        match get_user_access(&config) {
+            Ok(user_access) => client.set_user_access(user_access),
+            Err(err) => {
+                warnings.push(Warning::followers(&err));
+                followers = false;
+            }
+        }
+    }
+
+    let tag_cache = TagCache::load_cache();
+    let mut category_cache = CategoryCache::load_cache();
+    let mut follower_cache = FollowerCache::load_cache();
+    let Fetched {
+        streams,
+        mut excluded,
+        warnings: fetched,
+    } = if query.is_empty() {
+        // only the watchlist is shown
+        Fetched::default()
+    } else {
+        fetch_streams(
+            Search {
+                query: &query,
+                languages: &args.languages,
+                categories: &args.categories,
+                sort: args.sort,
+                limit: args.limit,
+                filter: &args.filter,
+                followers,
+                following: args.following,
+                everywhere: args.everywhere,
+                fuzzy: args.fuzzy,
+            },
+            &mut client,
+            &tag_cache,
+            &mut category_cache,
+            &mut follower_cache,
+        )?
+    };
+    warnings.extend(fetched);
 
-    let mut tag_cache = TagCache::load_cache();
     let mut streams: HashMap<_, Vec<_>> =
-        fetch_streams(&args.query, &args.languages, &app_access, &mut tag_cache)?
+        streams
             .into_iter()
             .fold(Default::default(), |mut map, (category, stream)| {
                 map.entry(category.clone()).or_default().push(stream);
                 map
             });
 
-    if let Err(..) = tag_cache.sync() {
-        // TODO report this
+    let watched = if config.watchlist.logins.is_empty() {
+        None
+    } else {
+        match fetch_watchlist(&mut client, &config.watchlist.logins, &args.filter) {
+            Ok(mut watched) => {
+                if !watched.unknown.is_empty() {
+                    warnings.push(Warning::unknown_logins(&watched.unknown));
+                }
+                warnings.append(&mut watched.warnings);
+                excluded.append(&mut watched.excluded);
+                sort_streams(&mut watched.live, args.sort);
+                Some(watched)
+            }
+            Err(err) => {
+                warnings.push(Warning::watchlist(&err));
+                None
+            }
+        }
+    };
+
+    report_retries(&client);
+    warnings.extend(client.take_warnings());
+
+    if let Err(err) = category_cache.sync() {
+        warnings.push(Warning::cache("categories", &err));
     }
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&streams)?);
-        std::process::exit(0)
+    if followers {
+        if let Err(err) = follower_cache.sync() {
+            warnings.push(Warning::cache("followers", &err));
+        }
     }
 
-    for streams in streams.values_mut() {
-        sort_streams(streams, args.sort)
+    report_warnings(&warnings);
+
+    // in strict mode, any warning is an error
+    let code = if args.strict && !warnings.is_empty() {
+        2
+    } else {
+        0
+    };
+
+    limit_streams(&mut streams, args.sort, args.limit);
+
+    if args.json {
+        let output = Output {
+            streams: &streams,
+            watchlist: watched.as_ref(),
+            excluded: &excluded,
+            warnings: &warnings,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        std::process::exit(code)
     }
 
     try_enable_colors();
 
-    let mut out = std::io::stdout().lock();
-    let streams = args
-        .query
-        .into_iter()
-        .filter_map(|q| streams.get(&*q).map(|s| (q, s.as_slice())));
+    let show_offline = args.show_offline || config.watchlist.show_offline;
 
-    render_streams(&mut out, &config, streams)
+    let mut groups = Vec::<Box<dyn Render>>::new();
+    if let Some(watched) = &watched {
+        if !watched.live.is_empty() {
+            groups.push(Box::new(entries(&config, "watchlist", &watched.live, true)));
+        }
+        if show_offline && !watched.offline.is_empty() {
+            groups.push(Box::new(Offline {
+                query: "not live",
+                logins: &watched.offline,
+            }));
+        }
+    }
+    groups.extend(query.iter().filter_map(|q| {
+        let streams = streams.get(&q.label)?;
+        Some(Box::new(entries(&config, &q.label, streams, args.everywhere)) as Box<dyn Render>)
+    }));
+
+    let mut out = std::io::stdout().lock();
+    render_groups(&mut out, &config, &groups)?;
+    out.flush()?;
+    std::process::exit(code)
 }
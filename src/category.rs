@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+
+use crate::{config::CategoryCache, Client};
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Category {
+    pub id: Box<str>,
+    pub name: Box<str>,
+    pub box_art_url: Box<str>,
+}
+
+/// Looks up categories that match each query
+///
+/// Exact matches (by name, or by id for numeric queries) are listed first, followed by the results of a category search
+pub fn lookup_categories<'a>(
+    query: &'a [String],
+    client: &Client,
+    cache: &mut CategoryCache,
+) -> anyhow::Result<Vec<(&'a String, Vec<Category>)>> {
+    query
+        .iter()
+        .map(|q| {
+            let key = if q.chars().all(|c| c.is_ascii_digit()) {
+                "id"
+            } else {
+                "name"
+            };
+
+            let mut categories = get_games(client, key, std::iter::once(&**q))?;
+            categories.extend(search_categories(client, q)?);
+
+            let mut seen = HashSet::new();
+            categories.retain(|c| seen.insert(c.id.clone()));
+
+            for category in &categories {
+                cache
+                    .cache
+                    .insert(category.name.to_lowercase().into(), category.id.clone());
+            }
+
+            Ok((q, categories))
+        })
+        .collect()
+}
+
+/// Resolves a list of categories into their game ids
+///
+/// A category can either be a numeric game id, which is used as-is, or the name of the category (e.g. `Just Chatting`)
+pub fn resolve_categories(
+    client: &Client,
+    categories: &[String],
+    cache: &mut CategoryCache,
+) -> anyhow::Result<Vec<String>> {
+    let (ids, names): (Vec<_>, Vec<_>) = categories
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .partition(|s| s.chars().all(|c| c.is_ascii_digit()));
+
+    let mut out: Vec<String> = ids.into_iter().map(ToString::to_string).collect();
+
+    let (known, unknown): (Vec<_>, Vec<_>) = names
+        .into_iter()
+        .partition(|name| cache.cache.contains_key(&*name.to_lowercase()));
+
+    out.extend(
+        known
+            .into_iter()
+            .map(|name| cache.cache[&*name.to_lowercase()].to_string()),
+    );
+
+    for names in unknown.chunks(100) {
+        let games = get_games(client, "name", names.iter().copied())?;
+        for name in names {
+            let game = games
+                .iter()
+                .find(|game| game.name.eq_ignore_ascii_case(name))
+                .with_context(|| format!("unknown category: {}", name))?;
+            cache
+                .cache
+                .insert(name.to_lowercase().into(), game.id.clone());
+            out.push(game.id.to_string());
+        }
+    }
+
+    out.sort_unstable();
+    out.dedup();
+    Ok(out)
+}
+
+/// Gets the categories with these game ids
+pub fn get_categories(client: &Client, ids: &[String]) -> anyhow::Result<Vec<Category>> {
+    let mut categories = vec![];
+    for ids in ids.chunks(100) {
+        categories.extend(get_games(client, "id", ids.iter().map(|s| &**s))?);
+    }
+    Ok(categories)
+}
+
+fn get_games<'a>(
+    client: &Client,
+    key: &str,
+    values: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Result<Vec<Category>> {
+    let query = std::iter::repeat(key).zip(values).collect::<Vec<_>>();
+    client.get_data("games", &query)
+}
+
+fn search_categories(client: &Client, query: &str) -> anyhow::Result<Vec<Category>> {
+    client.get_data("search/categories", &[("query", query), ("first", "20")])
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+
+use crate::{Client, Stream, User, Video};
+
+/// The information about a channel, from `helix/channels`
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Channel {
+    pub broadcaster_id: Box<str>,
+    pub broadcaster_login: Box<str>,
+    pub broadcaster_name: Box<str>,
+    #[serde(default)]
+    pub broadcaster_language: Box<str>,
+    #[serde(default)]
+    pub game_id: Box<str>,
+    #[serde(default)]
+    pub game_name: Box<str>,
+    #[serde(default)]
+    pub title: Box<str>,
+    #[serde(default)]
+    pub tags: Box<[Box<str>]>,
+    /// The content classification labels, e.g. `Gambling` or `ProfanityVulgarity`
+    #[serde(default)]
+    pub content_classification_labels: Box<[Box<str>]>,
+    #[serde(default)]
+    pub is_branded_content: bool,
+}
+
+/// Looks up channels by their broadcaster id, at most 100 at a time
+///
+/// The returned map is keyed by the broadcaster id
+pub fn get_channels<'a>(
+    client: &Client,
+    broadcaster_ids: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Result<HashMap<Box<str>, Channel>> {
+    let query = std::iter::repeat("broadcaster_id")
+        .zip(broadcaster_ids)
+        .collect::<Vec<_>>();
+    Ok(client
+        .get_data::<Channel>("channels", &query)?
+        .into_iter()
+        .map(|channel| (channel.broadcaster_id.clone(), channel))
+        .collect())
+}
+
+/// A channel found by `helix/search/channels`
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct SearchedChannel {
+    pub id: Box<str>,
+    pub broadcaster_login: Box<str>,
+    pub display_name: Box<str>,
+    #[serde(default)]
+    pub broadcaster_language: Box<str>,
+    #[serde(default)]
+    pub game_id: Box<str>,
+    #[serde(default)]
+    pub game_name: Box<str>,
+    #[serde(default)]
+    pub title: Box<str>,
+    #[serde(default)]
+    pub is_live: bool,
+}
+
+/// Searches for channels matching the query, in any category
+///
+/// This returns at most 100 channels
+// https://dev.twitch.tv/docs/api/reference/#search-channels
+pub fn search_channels(
+    client: &Client,
+    query: &str,
+    live_only: bool,
+) -> anyhow::Result<Vec<SearchedChannel>> {
+    let live_only = if live_only { "true" } else { "false" };
+    let query = [("query", query), ("live_only", live_only), ("first", "100")];
+    client.get_data("search/channels", &query)
+}
+
+/// Everything about a channel, whether it is live or not
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ChannelDetails {
+    pub user: User,
+    /// The stream, if the channel is live
+    pub stream: Option<Stream>,
+    pub channel: Option<Channel>,
+    /// The most recent past broadcast, this is only looked up if the channel isn't live
+    pub last_broadcast: Option<Video>,
+}
+
+impl ChannelDetails {
+    pub fn is_live(&self) -> bool {
+        self.stream.is_some()
+    }
+}
+
+/// Gets the details of the channel with this login
+pub fn get_channel_details(client: &mut Client, login: &str) -> anyhow::Result<ChannelDetails> {
+    client.retry_unauthorized(|client| try_get_channel_details(client, login))
+}
+
+fn try_get_channel_details(client: &Client, login: &str) -> anyhow::Result<ChannelDetails> {
+    let user = crate::get_users(client, "login", std::iter::once(login))?
+        .into_values()
+        .next()
+        .with_context(|| format!("unknown channel: {}", login))?;
+
+    let stream = client
+        .get_data::<Stream>("streams", &[("user_id", &user.id)])?
+        .into_iter()
+        .next()
+        .map(|mut stream| {
+            stream.set_user(&user);
+            stream.fix_uptime();
+            stream
+        });
+
+    let channel = get_channels(client, std::iter::once(&*user.id))?.remove(&user.id);
+
+    let last_broadcast = match stream {
+        Some(..) => None,
+        None => crate::get_last_broadcast(client, &user.id)?,
+    };
+
+    Ok(ChannelDetails {
+        user,
+        stream,
+        channel,
+        last_broadcast,
+    })
+}
@@ -1,29 +1,163 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Context;
 
-#[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
+/// The path of a file in the cache directory
+pub(crate) fn cache_path(name: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|f| f.join("museun").join("what_stream").join(name))
+}
+
+/// Reads a json file, if it exists and is valid
+pub(crate) fn load_json<T>(path: Option<PathBuf>) -> Option<T>
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    path.and_then(|p| std::fs::read(p).ok())
+        .and_then(|s| serde_json::from_slice(&s).ok())
+}
+
+/// Writes a json file, replacing it. the directories it is in are created if they don't exist
+pub(crate) fn save_json<T>(path: Option<&Path>, value: &T) -> anyhow::Result<()>
+where
+    T: serde::Serialize,
+{
+    let path = path.with_context(|| "cannot get the cache path")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer(&mut writer, value)?;
+    Ok(())
+}
+
+/// Writes a json file that only we should be able to read (0600 on unix), e.g. a token.
+/// the directories it is in are created if they don't exist
+pub(crate) fn write_private_json<T>(path: &Path, value: &T) -> anyhow::Result<()>
+where
+    T: serde::Serialize,
+{
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut writer = std::io::BufWriter::new(
+        options
+            .open(path)
+            .with_context(|| format!("cannot write {}", path.to_string_lossy()))?,
+    );
+    serde_json::to_writer(&mut writer, value)?;
+    Ok(())
+}
+
+/// Writes a cache, unless it is the same as the stored one
+fn sync_cache<T>(path: Option<PathBuf>, value: &T) -> anyhow::Result<()>
+where
+    T: for<'de> serde::Deserialize<'de> + serde::Serialize + PartialEq + Default,
+{
+    // a missing cache is the same as an empty one
+    if load_json::<T>(path.clone()).unwrap_or_default() == *value {
+        return Ok(());
+    }
+    save_json(path.as_deref(), value)
+}
+
+/// A cache of tag ids to their names, from Twitch's old tag system
+#[derive(Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TagCache {
+    #[serde(default)]
     pub cache: HashMap<Box<str>, Box<str>>,
 }
 
 impl TagCache {
     pub fn get_cache_path() -> Option<PathBuf> {
-        dirs::cache_dir().map(|f| f.join("museun").join("what_stream").join("tags_cache.json"))
+        cache_path("tags_cache.json")
     }
 
     pub fn load_cache() -> Self {
-        Self::get_cache_path()
-            .and_then(|p| std::fs::read(p).ok())
-            .and_then(|s| serde_json::from_slice(&*s).ok())
-            .unwrap_or_default()
+        load_json(Self::get_cache_path()).unwrap_or_default()
     }
 
     pub fn sync(&self) -> anyhow::Result<()> {
-        let path = Self::get_cache_path().with_context(|| "cannot get the cache path")?;
-        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
-        serde_json::to_writer(&mut writer, self)?;
-        Ok(())
+        sync_cache(Self::get_cache_path(), self)
+    }
+}
+
+/// A cache of category names (lowercased) to their game ids
+#[derive(Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CategoryCache {
+    pub cache: HashMap<Box<str>, Box<str>>,
+}
+
+impl CategoryCache {
+    pub fn get_cache_path() -> Option<PathBuf> {
+        cache_path("categories_cache.json")
+    }
+
+    pub fn load_cache() -> Self {
+        load_json(Self::get_cache_path()).unwrap_or_default()
+    }
+
+    pub fn sync(&self) -> anyhow::Result<()> {
+        sync_cache(Self::get_cache_path(), self)
+    }
+}
+
+// How long a follower count is used before it is fetched again, in seconds
+const FOLLOWERS_TTL: i64 = 6 * 60 * 60;
+
+/// A cache of user ids to their follower counts
+#[derive(Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FollowerCache {
+    pub cache: HashMap<Box<str>, FollowerCount>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FollowerCount {
+    pub total: u64,
+    /// When the count was fetched, as a unix timestamp
+    pub fetched_at: i64,
+}
+
+impl FollowerCache {
+    pub fn get_cache_path() -> Option<PathBuf> {
+        cache_path("followers_cache.json")
+    }
+
+    pub fn load_cache() -> Self {
+        let mut this: Self = load_json(Self::get_cache_path()).unwrap_or_default();
+
+        let now = chrono::Utc::now().timestamp();
+        this.cache
+            .retain(|_, count| now - count.fetched_at < FOLLOWERS_TTL);
+        this
+    }
+
+    pub fn sync(&self) -> anyhow::Result<()> {
+        sync_cache(Self::get_cache_path(), self)
+    }
+
+    /// Gets the follower count for a user, if it was fetched recently
+    pub fn get(&self, user_id: &str) -> Option<u64> {
+        let now = chrono::Utc::now().timestamp();
+        self.cache
+            .get(user_id)
+            .filter(|count| now - count.fetched_at < FOLLOWERS_TTL)
+            .map(|count| count.total)
+    }
+
+    pub fn insert(&mut self, user_id: &str, total: u64) {
+        let fetched_at = chrono::Utc::now().timestamp();
+        self.cache
+            .insert(user_id.into(), FollowerCount { total, fetched_at });
     }
 }
 
@@ -32,6 +166,14 @@ impl TagCache {
 pub struct Config {
     pub parameters: Parameters,
     pub appearance: Appearance,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<super::Credentials>,
+    #[serde(default)]
+    pub endpoints: super::http::Endpoints,
+    #[serde(default)]
+    pub watchlist: super::Watchlist,
+    #[serde(default)]
+    pub aliases: super::Aliases,
 }
 
 impl Config {
@@ -43,7 +185,54 @@ impl Config {
         r##"
 [parameters]
 languages    = ["en"]
+# each entry can also be an expression, e.g. 'rust AND (bevy OR wgpu)'
 query        = ["rust"]
+# either numeric game ids or category names, e.g. "Just Chatting"
+# defaults to 'Science & Technology' and 'Software and Game Development'
+categories   = ["509670", "1469308723"]
+# the most pages (of 100 streams) to fetch. unset means every page
+# max_pages    = 10
+# fetch the follower count of each matched streamer (this is also enabled by --followers)
+followers    = false
+# hide streams that are marked as mature (this is also enabled by --hide-mature)
+hide_mature  = false
+# hide streams with any of these content classification labels, e.g. "Gambling" or "ProfanityVulgarity"
+hide_labels  = []
+# hide streams that aren't live, e.g. reruns (this is also enabled by --live-only)
+live_only    = false
+# let the query match loosely, e.g. "Rust's", "rustlang" and "game-dev" (this is also enabled by --fuzzy)
+fuzzy        = false
+
+# the client id and secret of your Twitch application can be provided here
+# they can also be set with the WHAT_STREAM_CLIENT_ID and WHAT_STREAM_CLIENT_SECRET env vars
+# or in a separate credentials file (see --print-credentials-path)
+# [credentials]
+# client_id     = "your client id"
+# client_secret = "your client secret"
+
+# the base urls for the Twitch APIs, e.g. for a mock server or a caching gateway
+# they can also be set with the WHAT_STREAM_API_URL and WHAT_STREAM_AUTH_URL env vars
+[endpoints]
+api          = "https://api.twitch.tv/helix"
+auth         = "https://id.twitch.tv/oauth2"
+
+# channels that are always shown when they are live, whatever they are streaming
+[watchlist]
+logins       = []
+# also list the channels that aren't live (this is also enabled by --show-offline)
+show_offline = false
+
+# alternative spellings of query terms, a match of any of them counts as the name on the left
+# so the streams are shown in one group, e.g. 'cpp' rather than 'cpp', 'c++' and 'cplusplus'
+[aliases]
+# cpp          = ["c++", "cplusplus"]
+# js           = ["javascript", "typescript", "node"]
+
+[appearance]
+# show the display name of a streamer (e.g. 'Museun' or 'キャロル') rather than their login
+display_names = false
+# show whether a streamer is a 'partner' or an 'affiliate'
+badges        = false
 
 [appearance.glyphs]
 # TODO explain these
@@ -69,6 +258,7 @@ title           = { fg = "#C19C00", bold = true }
 uptime          = { fg = "#13A10E" }
 viewers         = { fg = "#3A96DD" }
 tags            = { fg = "#404040" }
+badge           = { fg = "#B4009E" }
 "##
     }
 }
@@ -77,10 +267,30 @@ tags            = { fg = "#404040" }
 pub struct Parameters {
     pub languages: Vec<String>,
     pub query: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pages: Option<usize>,
+    #[serde(default)]
+    pub followers: bool,
+    #[serde(default)]
+    pub hide_mature: bool,
+    #[serde(default)]
+    pub hide_labels: Vec<String>,
+    #[serde(default)]
+    pub live_only: bool,
+    #[serde(default)]
+    pub fuzzy: bool,
 }
 
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Appearance {
+    /// Show the display name of a user rather than their login
+    #[serde(default)]
+    pub display_names: bool,
+    /// Show whether a user is a partner or an affiliate
+    #[serde(default)]
+    pub badges: bool,
     pub glyphs: super::Style,
     pub colors: super::Theme,
 }
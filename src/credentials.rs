@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+
+use crate::Config;
+
+/// The client id and client secret of your registered Twitch application
+///
+/// Note: You must register an application at https://dev.twitch.tv/console
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Credentials {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl Credentials {
+    pub const CLIENT_ID_ENV: &'static str = "WHAT_STREAM_CLIENT_ID";
+    pub const CLIENT_SECRET_ENV: &'static str = "WHAT_STREAM_CLIENT_SECRET";
+
+    pub fn get_credentials_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|f| {
+            f.join("museun")
+                .join("what_stream")
+                .join("credentials.toml")
+        })
+    }
+
+    /// Loads the credentials, in order of precedence, from:
+    /// - the `WHAT_STREAM_CLIENT_ID` and `WHAT_STREAM_CLIENT_SECRET` env vars
+    /// - the `[credentials]` section of the configuration
+    /// - the credentials file (see [`Credentials::get_credentials_path`])
+    /// - the values embedded at compile time, if the `embed-credentials` feature is enabled
+    pub fn load(config: &Config) -> anyhow::Result<Self> {
+        if let Some(credentials) = Self::from_env()? {
+            return Ok(credentials);
+        }
+
+        if let Some(credentials) = config.credentials.clone().filter(Self::is_complete) {
+            return Ok(credentials);
+        }
+
+        if let Some(path) = Self::get_credentials_path().filter(|p| p.is_file()) {
+            return Self::from_file(&path);
+        }
+
+        Self::embedded().with_context(|| {
+            format!(
+                "no Twitch client credentials were found. set `{id}` and `{secret}`, \
+                 add a [credentials] section to your configuration, \
+                 or create {path} (with 0600 permissions) containing `client_id` and `client_secret`",
+                id = Self::CLIENT_ID_ENV,
+                secret = Self::CLIENT_SECRET_ENV,
+                path = Self::get_credentials_path()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "a credentials file".to_string()),
+            )
+        })
+    }
+
+    // having only one of them set is probably a mistake, so it isn't silently ignored
+    fn from_env() -> anyhow::Result<Option<Self>> {
+        let var = |name| std::env::var(name).ok().filter(|s| !s.trim().is_empty());
+        match (var(Self::CLIENT_ID_ENV), var(Self::CLIENT_SECRET_ENV)) {
+            (Some(client_id), Some(client_secret)) => Ok(Some(Self {
+                client_id,
+                client_secret,
+            })),
+            (Some(..), None) => anyhow::bail!(
+                "`{}` is set, but `{}` is not",
+                Self::CLIENT_ID_ENV,
+                Self::CLIENT_SECRET_ENV
+            ),
+            (None, Some(..)) => anyhow::bail!(
+                "`{}` is set, but `{}` is not",
+                Self::CLIENT_SECRET_ENV,
+                Self::CLIENT_ID_ENV
+            ),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn from_file(path: &Path) -> anyhow::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            let mode = std::fs::metadata(path)?.permissions().mode();
+            anyhow::ensure!(
+                mode & 0o077 == 0,
+                "{} is accessible by other users (mode {:o}). run `chmod 600` on it",
+                path.to_string_lossy(),
+                mode & 0o777
+            );
+        }
+
+        let data = std::fs::read(path)?;
+        let credentials: Self = toml::from_slice(&data)
+            .with_context(|| format!("invalid toml in {}", path.to_string_lossy()))?;
+
+        anyhow::ensure!(
+            Self::is_complete(&credentials),
+            "{} must contain both `client_id` and `client_secret`",
+            path.to_string_lossy()
+        );
+        Ok(credentials)
+    }
+
+    #[cfg(feature = "embed-credentials")]
+    fn embedded() -> Option<Self> {
+        Some(Self {
+            client_id: env!("WHAT_STREAM_CLIENT_ID").to_string(),
+            client_secret: env!("WHAT_STREAM_CLIENT_SECRET").to_string(),
+        })
+    }
+
+    #[cfg(not(feature = "embed-credentials"))]
+    fn embedded() -> Option<Self> {
+        None
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.client_id.trim().is_empty() && !self.client_secret.trim().is_empty()
+    }
+}
@@ -0,0 +1,115 @@
+use crate::Stream;
+
+/// Which of the matched streams to hide
+///
+/// Streams without a follower count (e.g. if it could not be fetched) are not filtered by it
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    pub min_followers: Option<u64>,
+    pub max_followers: Option<u64>,
+    /// Hide streams marked as mature
+    pub hide_mature: bool,
+    /// Hide streams with any of these content classification labels, e.g. `Gambling`
+    pub hide_labels: Vec<String>,
+    /// Hide streams that aren't `live`, e.g. reruns
+    pub live_only: bool,
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        self.hide_mature || self.live_only || self.is_deferred()
+    }
+
+    // whether this needs more than what `helix/streams` returns to decide
+    pub(crate) fn is_deferred(&self) -> bool {
+        self.min_followers.is_some() || self.max_followers.is_some() || !self.hide_labels.is_empty()
+    }
+
+    /// Only the filters about what is being streamed, e.g. for the watchlist
+    pub fn content(&self) -> Self {
+        Self {
+            hide_mature: self.hide_mature,
+            hide_labels: self.hide_labels.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn matches(&self, stream: &Stream) -> bool {
+        self.reasons(stream).is_empty()
+    }
+
+    /// Why the stream should be hidden, this is empty if it should be shown
+    pub fn reasons(&self, stream: &Stream) -> Vec<Reason> {
+        let mut reasons = vec![];
+
+        if self.live_only && &*stream.kind != "live" {
+            reasons.push(Reason::NotLive {
+                kind: stream.kind.clone(),
+            });
+        }
+
+        if self.hide_mature && stream.is_mature {
+            reasons.push(Reason::Mature);
+        }
+
+        reasons.extend(
+            stream
+                .content_classification_labels
+                .iter()
+                .filter(|label| {
+                    self.hide_labels
+                        .iter()
+                        .any(|l| l.eq_ignore_ascii_case(label))
+                })
+                .map(|label| Reason::Label {
+                    label: label.clone(),
+                }),
+        );
+
+        if let Some(followers) = stream.followers {
+            match (self.min_followers, self.max_followers) {
+                (Some(min), _) if followers < min => {
+                    reasons.push(Reason::TooFewFollowers { followers, min })
+                }
+                (_, Some(max)) if followers > max => {
+                    reasons.push(Reason::TooManyFollowers { followers, max })
+                }
+                _ => {}
+            }
+        }
+
+        reasons
+    }
+}
+
+/// Why a stream was hidden by the [`Filter`]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Reason {
+    /// The stream isn't `live`, the `kind` is the type of the stream
+    NotLive {
+        kind: Box<str>,
+    },
+    Mature,
+    /// The stream has a hidden content classification label
+    Label {
+        label: Box<str>,
+    },
+    TooFewFollowers {
+        followers: u64,
+        min: u64,
+    },
+    TooManyFollowers {
+        followers: u64,
+        max: u64,
+    },
+}
+
+/// A matched stream that was hidden by the [`Filter`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Excluded {
+    pub query: String,
+    pub user_name: Box<str>,
+    pub user_id: Box<str>,
+    pub reasons: Vec<Reason>,
+}
@@ -0,0 +1,18 @@
+use crate::Client;
+
+/// Gets the number of followers a broadcaster has
+///
+/// This needs a user access token, see [`Client::set_user_access`]
+// https://dev.twitch.tv/docs/api/reference/#get-channel-followers
+pub fn get_followers(client: &Client, broadcaster_id: &str) -> anyhow::Result<u64> {
+    #[derive(serde::Deserialize)]
+    struct Resp {
+        total: u64,
+    }
+
+    // only the total is needed, so don't ask for more than a single follower
+    let query = [("broadcaster_id", broadcaster_id), ("first", "1")];
+    Ok(client
+        .get_json_as_user::<Resp>("channels/followers", &query)?
+        .total)
+}
@@ -0,0 +1,119 @@
+// Loose word matching, for when exact matching is too strict. e.g. `Rust's`, `rustlang` and `game-dev`
+
+// What can follow a term in a word that is still about it, e.g. `rustlang`, `rustaceans` and `gamedev`.
+// any other ending is a different word, e.g. `javascript` isn't `java`
+const AFFIXES: &[&str] = &["lang", "acean", "aceans", "dev"];
+
+// Longer words may differ by more edits, e.g. `programing` for `programming`
+fn max_distance(len: usize) -> usize {
+    match len {
+        0..=4 => 0,
+        5..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Whether a word loosely matches a term, both are compared case-insensitively
+///
+/// They match if they're the same after normalization or stemming, if the word is the term
+/// with a known affix after it (e.g. `lang`), or if they're a few edits apart (for longer terms)
+pub(crate) fn matches(term: &str, word: &str) -> bool {
+    let (term, word) = (normalize(term), normalize(word));
+    if term.is_empty() || word.is_empty() {
+        return false;
+    }
+
+    if term == word || stem(&term) == stem(&word) {
+        return true;
+    }
+
+    let affixed = word
+        .strip_prefix(&term)
+        .is_some_and(|rest| AFFIXES.contains(&rest));
+    if affixed {
+        return true;
+    }
+
+    let max = max_distance(term.chars().count());
+    max > 0 && distance(&term, &word) <= max
+}
+
+// lowercases the word, removes a possessive and removes hyphens. e.g. `Rust's` is `rust` and `Game-Dev` is `gamedev`
+fn normalize(word: &str) -> String {
+    let word = word.to_lowercase();
+    let word = ["'s", "’s", "'", "’"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(&word);
+    word.replace('-', "")
+}
+
+// removes a common suffix, as long as enough of the word is left. e.g. `streams` is `stream`
+fn stem(word: &str) -> &str {
+    const SUFFIXES: &[&str] = &["ing", "ers", "er", "ed", "s"];
+    SUFFIXES
+        .iter()
+        .filter_map(|suffix| word.strip_suffix(suffix))
+        .find(|stem| stem.chars().count() >= 3)
+        .unwrap_or(word)
+}
+
+// the levenshtein distance between two words
+fn distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+    let mut current = vec![0; right.len() + 1];
+
+    for (i, l) in left.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, r) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(l != *r);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words() {
+        let words = [
+            ("rust", "rust", true),
+            ("rust", "RUST", true),
+            ("rust", "rustlang", true),
+            ("rust", "Rust's", true),
+            ("rust", "rust’s", true),
+            ("rust", "rustaceans", true),
+            ("gamedev", "game-dev", true),
+            ("game-dev", "gamedev", true),
+            ("stream", "streams", true),
+            ("streaming", "streamers", true),
+            ("zig", "zigs", true),
+            ("programming", "programing", true),
+            ("python", "pyton", true),
+            ("go", "golang", true),
+            ("game", "gamedev", true),
+            ("java", "javascript", false),
+            ("rust", "rusty", false),
+            ("rust", "rustc", false),
+            ("go", "gopher", false),
+            ("go", "got", false),
+            ("rust", "trust", false),
+            ("rust", "rest", false),
+            ("bevy", "heavy", false),
+            ("python", "typhon", false),
+            ("c", "c++", false),
+            ("", "rust", false),
+            ("'", "rust", false),
+        ];
+
+        for (term, word, expected) in words {
+            assert_eq!(matches(term, word), expected, "{} {}", term, word);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+
+use crate::{
+    http::{Endpoints, Method, Request, Response, StatusError, Transport},
+    AppAccess, Credentials, UserAccess, Warning,
+};
+
+// How many times a request is retried before giving up
+const MAX_RETRIES: u32 = 4;
+
+// The initial delay for the exponential backoff
+const BASE_DELAY: Duration = Duration::from_millis(250);
+
+// The longest we'll wait for the rate limit to reset
+const MAX_RESET_WAIT: Duration = Duration::from_secs(60);
+
+/// A client for the Helix API
+///
+/// Requests are throttled by the `Ratelimit-Remaining` and `Ratelimit-Reset` headers,
+/// and transient failures (transport errors, `429` and `5xx`) are retried with a jittered exponential backoff
+pub struct Client {
+    transport: Box<dyn Transport>,
+    endpoints: Endpoints,
+    app_access: AppAccess,
+    user_access: Option<UserAccess>,
+    rate_limit: Mutex<RateLimit>,
+    retries: AtomicUsize,
+    warnings: Vec<Warning>,
+}
+
+#[derive(Default, Debug)]
+struct RateLimit {
+    remaining: Option<u64>,
+    // unix timestamp of when the bucket refills
+    reset: Option<i64>,
+}
+
+impl RateLimit {
+    fn update(&mut self, resp: &Response) {
+        let parse = |name| resp.header(name).and_then(|s| s.trim().parse().ok());
+        if let Some(remaining) = parse("ratelimit-remaining") {
+            self.remaining = Some(remaining);
+        }
+        if let Some(reset) = parse("ratelimit-reset") {
+            self.reset = Some(reset as i64);
+        }
+    }
+
+    fn time_until_reset(&self) -> Option<Duration> {
+        let reset = self.reset? - chrono::Utc::now().timestamp();
+        // wait for at least a second, the reset is only second-granular
+        Some(Duration::from_secs(reset.max(1) as u64).min(MAX_RESET_WAIT))
+    }
+
+    fn wait_time(&self) -> Option<Duration> {
+        match (self.remaining, self.reset) {
+            (Some(0), Some(reset)) if reset > chrono::Utc::now().timestamp() => {
+                self.time_until_reset()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Client {
+    /// Creates a new client, getting an app access token with the credentials
+    pub fn new(
+        transport: impl Transport + 'static,
+        endpoints: Endpoints,
+        credentials: &Credentials,
+    ) -> anyhow::Result<Self> {
+        let (app_access, warning) = AppAccess::get(&transport, &endpoints, credentials)?;
+        Ok(Self {
+            transport: Box::new(transport),
+            endpoints,
+            app_access,
+            user_access: None,
+            rate_limit: Mutex::default(),
+            retries: AtomicUsize::new(0),
+            warnings: warning.into_iter().collect(),
+        })
+    }
+
+    /// How many requests have been retried so far
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Takes the problems the client ran into so far, e.g. the token not being cached
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.app_access.client_id
+    }
+
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    pub fn transport(&self) -> &dyn Transport {
+        &*self.transport
+    }
+
+    /// Uses the user access token for [`Client::get_as_user`]
+    pub fn set_user_access(&mut self, user_access: UserAccess) {
+        self.user_access = Some(user_access);
+    }
+
+    pub fn user_access(&self) -> Option<&UserAccess> {
+        self.user_access.as_ref()
+    }
+
+    /// Replaces the access tokens with new ones
+    pub fn renew(&mut self) -> anyhow::Result<()> {
+        let warning = self.app_access.renew(&*self.transport, &self.endpoints)?;
+        self.warnings.extend(warning);
+        if let Some(user_access) = &mut self.user_access {
+            user_access.renew(&*self.transport, &self.endpoints)?;
+        }
+        Ok(())
+    }
+
+    /// Calls `f`, and if the token was rejected, renews the tokens and calls it again
+    pub fn retry_unauthorized<T>(
+        &mut self,
+        mut f: impl FnMut(&Self) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        match f(self) {
+            // the token was revoked or expired early, so get a new one and try again
+            Err(err) if is_unauthorized(&err) => {
+                self.renew()?;
+                f(self)
+            }
+            result => result,
+        }
+    }
+
+    /// Sends a `GET` to the Helix `path`, e.g. `streams`
+    pub fn get(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<Response> {
+        self.send_get(&self.app_access.access_token, path, query)
+    }
+
+    /// Sends a `GET` to the Helix `path` with the user access token, e.g. `streams/followed`
+    pub fn get_as_user(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<Response> {
+        let user_access = self
+            .user_access
+            .as_ref()
+            .with_context(|| "you are not logged in, run `what_stream login` first")?;
+        self.send_get(&user_access.access_token, path, query)
+    }
+
+    fn send_get(
+        &self,
+        token: &str,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> anyhow::Result<Response> {
+        let url = self.endpoints.api(path);
+        let authorization = format!("Bearer {}", token);
+        let headers = [
+            ("client-id", self.client_id()),
+            ("authorization", &*authorization),
+        ];
+        let request = Request {
+            method: Method::Get,
+            url: &url,
+            query,
+            headers: &headers,
+        };
+
+        let mut attempt = 0;
+        loop {
+            // if we've used up our budget, wait for it to refill
+            let wait = self.rate_limit.lock().unwrap().wait_time();
+            if let Some(wait) = wait {
+                std::thread::sleep(wait);
+            }
+
+            let result = self.transport.send(request.clone());
+            if let Ok(resp) = &result {
+                self.rate_limit.lock().unwrap().update(resp);
+            }
+
+            let delay = match &result {
+                Ok(resp) if resp.status == 429 => {
+                    let reset = self.rate_limit.lock().unwrap().time_until_reset();
+                    reset.unwrap_or_else(|| backoff(attempt))
+                }
+                Ok(resp) if resp.status >= 500 => backoff(attempt),
+                Ok(..) => break result?.error_for_status().map_err(Into::into),
+                Err(..) => backoff(attempt),
+            };
+
+            if attempt == MAX_RETRIES {
+                break result?.error_for_status().map_err(Into::into);
+            }
+
+            attempt += 1;
+            self.retries.fetch_add(1, Ordering::Relaxed);
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// Sends a `GET` to the Helix `path` and deserializes the response
+    pub fn get_json<T>(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.get(path, query)?.json()
+    }
+
+    /// Sends a `GET` to the Helix `path` and deserializes the `data` of the response, e.g. the users
+    pub fn get_data<T>(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<Vec<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        Ok(self.get_json::<Data<Vec<T>>>(path, query)?.data)
+    }
+
+    /// Sends a `GET` to the Helix `path` with the user access token and deserializes the response
+    pub fn get_json_as_user<T>(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.get_as_user(path, query)?.json()
+    }
+}
+
+/// The `{ "data": .. }` that the Helix responses are wrapped in
+#[derive(serde::Deserialize)]
+pub(crate) struct Data<T> {
+    pub(crate) data: T,
+    /// Where the next page starts, for the endpoints that have pages
+    #[serde(default)]
+    pub(crate) pagination: Pagination,
+}
+
+#[derive(Default, serde::Deserialize)]
+pub(crate) struct Pagination {
+    #[serde(default)]
+    pub(crate) cursor: String,
+}
+
+/// Whether the error was caused by the token being rejected
+pub fn is_unauthorized(err: &anyhow::Error) -> bool {
+    error_status(err) == Some(401)
+}
+
+/// Whether the error was caused by the request being rejected, e.g. an invalid parameter
+pub fn is_bad_request(err: &anyhow::Error) -> bool {
+    error_status(err) == Some(400)
+}
+
+/// Whether the error was caused by the resource not existing
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    error_status(err) == Some(404)
+}
+
+fn error_status(err: &anyhow::Error) -> Option<u16> {
+    err.downcast_ref::<StatusError>().map(|err| err.status)
+}
+
+// exponential backoff with 'equal jitter'
+fn backoff(attempt: u32) -> Duration {
+    use std::hash::{BuildHasher as _, Hasher as _};
+
+    let max = BASE_DELAY.as_millis() as u64 * 2_u64.pow(attempt);
+    // RandomState is randomly seeded, which is random enough for jitter
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    Duration::from_millis(max / 2 + random % (max / 2 + 1))
+}
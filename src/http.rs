@@ -0,0 +1,183 @@
+use std::io::Read as _;
+
+use crate::Config;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Request<'a> {
+    pub method: Method,
+    pub url: &'a str,
+    pub query: &'a [(&'a str, &'a str)],
+    pub headers: &'a [(&'a str, &'a str)],
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Gets the value of a header, the name is case-insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find_map(|(k, v)| k.eq_ignore_ascii_case(name).then_some(&**v))
+    }
+
+    pub const fn is_success(&self) -> bool {
+        matches!(self.status, 200..=299)
+    }
+
+    /// Turns a non-successful response into a [`StatusError`]
+    pub fn error_for_status(self) -> Result<Self, StatusError> {
+        if self.is_success() {
+            return Ok(self);
+        }
+        Err(StatusError {
+            status: self.status,
+            body: String::from_utf8_lossy(&self.body).into_owned(),
+        })
+    }
+
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
+        serde_json::from_slice(&self.body).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusError {
+    pub status: u16,
+    pub body: String,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the server responded with status {}", self.status)?;
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body.trim())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Something that can send a http request
+///
+/// Non-successful status codes should be returned as a [`Response`], errors are for transport failures
+pub trait Transport: Send + Sync {
+    fn send(&self, request: Request<'_>) -> anyhow::Result<Response>;
+}
+
+/// The default [`Transport`], using `ureq`
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self {
+            agent: ureq::agent(),
+        }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: Request<'_>) -> anyhow::Result<Response> {
+        let req = request.query.iter().fold(
+            self.agent.request(request.method.as_str(), request.url),
+            |req, (k, v)| req.query(k, v),
+        );
+
+        let req = request
+            .headers
+            .iter()
+            .fold(req, |req, (k, v)| req.set(k, v));
+
+        let resp = match req.call() {
+            Ok(resp) | Err(ureq::Error::Status(_, resp)) => resp,
+            Err(err) => return Err(err.into()),
+        };
+
+        let status = resp.status();
+        let headers = resp
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = resp.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+
+        let mut body = Vec::new();
+        resp.into_reader().read_to_end(&mut body)?;
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// The base urls for the Twitch APIs
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Endpoints {
+    /// The base url for the Helix API
+    pub api: String,
+    /// The base url for the OAuth2 API
+    pub auth: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            api: String::from("https://api.twitch.tv/helix"),
+            auth: String::from("https://id.twitch.tv/oauth2"),
+        }
+    }
+}
+
+impl Endpoints {
+    pub const API_URL_ENV: &'static str = "WHAT_STREAM_API_URL";
+    pub const AUTH_URL_ENV: &'static str = "WHAT_STREAM_AUTH_URL";
+
+    /// Loads the endpoints from the configuration, the env vars take precedence
+    pub fn load(config: &Config) -> Self {
+        let mut this = config.endpoints.clone();
+        if let Ok(api) = std::env::var(Self::API_URL_ENV) {
+            this.api = api;
+        }
+        if let Ok(auth) = std::env::var(Self::AUTH_URL_ENV) {
+            this.auth = auth;
+        }
+        this.api.truncate(this.api.trim_end_matches('/').len());
+        this.auth.truncate(this.auth.trim_end_matches('/').len());
+        this
+    }
+
+    pub fn api(&self, path: &str) -> String {
+        format!("{}/{}", self.api, path)
+    }
+
+    pub fn auth(&self, path: &str) -> String {
+        format!("{}/{}", self.auth, path)
+    }
+}
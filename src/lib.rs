@@ -1,30 +1,82 @@
-// Note: You must register an application at https://dev.twitch.tv/console
+// This is the default 'Science and Tech' category
+pub const SCIENCE_AND_TECH_CATEGORY: &str = "509670";
 
-// The client id for your application
-pub const WHAT_STREAM_CLIENT_ID: &str = env!("WHAT_STREAM_CLIENT_ID");
+// This is the default 'Software and Game development' category
+pub const SOFTWARE_AND_GAME_DEV_CATEGORY: &str = "1469308723";
 
-// The client secret for your application
-pub const WHAT_STREAM_CLIENT_SECRET: &str = env!("WHAT_STREAM_CLIENT_SECRET");
+// These are the categories used when none are configured
+pub const DEFAULT_CATEGORIES: [&str; 2] =
+    [SCIENCE_AND_TECH_CATEGORY, SOFTWARE_AND_GAME_DEV_CATEGORY];
 
-// TODO allow for custom categories
-// TODO provide a utlity for looking up category ideas for a query
+mod args;
+pub use args::{Args, Command};
 
-// This is hardcoded to look at specific category, namely 'Science and Tech'
-pub const SCIENCE_AND_TECH_CATEGORY: &str = "509670";
+mod access;
+pub use access::AppAccess;
 
-// This is hardcoded to look at specific category, namely 'Software and Game development'
-pub const SOFTWARE_AND_GAME_DEV_CATEGORY: &str = "1469308723";
+mod user_access;
+pub use user_access::{DeviceCode, UserAccess};
 
-mod args;
-pub use args::{AppAccess, Args};
+mod token;
+
+pub mod http;
+
+mod helix;
+pub use helix::Client;
 
 mod render;
-pub use render::{Demo, Entries, Render, Style, Theme};
+pub use render::{
+    Categories, Demo, Details, Entries, Offline, Popularity, Render, Style, Theme, Upcoming,
+};
+
+mod credentials;
+pub use credentials::Credentials;
 
 mod config;
-pub use config::{Appearance, Config, Parameters, TagCache};
+pub use config::{Appearance, CategoryCache, Config, FollowerCache, Parameters, TagCache};
+
+mod category;
+pub use category::{get_categories, lookup_categories, resolve_categories, Category};
+
+mod query;
+pub use query::{Aliases, Expr, MatchKind, Pattern, Query, QueryError};
+
+mod fuzzy;
+mod tokenizer;
 
 mod stream;
-pub use stream::{fetch_streams, sort_streams, Stream};
+pub use stream::{fetch_streams, limit_streams, sort_streams, Fetched, Limit, Search, Stream};
+
+mod channel;
+pub use channel::{
+    get_channel_details, get_channels, search_channels, Channel, ChannelDetails, SearchedChannel,
+};
+
+mod filter;
+pub use filter::{Excluded, Filter, Reason};
+
+mod followers;
+pub use followers::get_followers;
+
+mod user;
+pub use user::{get_users, User};
+
+mod video;
+pub use video::{get_last_broadcast, Video};
+
+mod watchlist;
+pub use watchlist::{fetch_watchlist, Watched, Watchlist};
+
+mod schedule;
+pub use schedule::{
+    fetch_schedules, get_schedule, Schedule, Schedules, Segment, SegmentCategory, Vacation,
+};
+
+mod top;
+pub use top::{count_categories, get_top_categories, CategoryStats, Top};
+
+mod warning;
+pub use warning::Warning;
 
+mod pool;
 mod string;
@@ -0,0 +1,36 @@
+// Runs a blocking function over a list of items on a few threads
+
+/// Calls `f` with each of the items, on at most `workers` threads
+///
+/// Each thread gets its share of the items and goes through them one at a time.
+/// The results are paired with their items, in the same order as the items
+pub(crate) fn map_shared<'a, T, R>(
+    items: &'a [T],
+    workers: usize,
+    f: impl Fn(&'a T) -> R + Sync,
+) -> Vec<(&'a T, R)>
+where
+    T: Sync,
+    R: Send,
+{
+    let share = items.len().div_ceil(workers.max(1)).max(1);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let workers = items
+            .chunks(share)
+            .map(|items| {
+                scope.spawn(move || items.iter().map(|item| (item, f(item))).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                // a panic in a worker is a panic here
+                worker
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,866 @@
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use crate::Stream;
+
+/// One group of the query, the streams matching its expression are shown together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// The expression, as it is shown in the output
+    pub label: String,
+    pub expr: Expr,
+}
+
+/// A boolean expression of terms
+///
+/// Terms are case-insensitive and match a word in the title of a stream, or one of its tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// A single word, e.g. `rust`
+    Term(String),
+    /// Consecutive words, e.g. `"game engine"`
+    Phrase(Vec<String>),
+    /// A regular expression, which is matched against the whole title
+    Pattern(Pattern),
+    /// A term that has aliases, any of the alternatives match it. the first alternative is the name
+    Aliased {
+        name: String,
+        alternatives: Vec<Vec<String>>,
+    },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+/// A regular expression written as `/pattern/flags`, e.g. `/advent ?of ?code/i`
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    text: String,
+    regex: regex::Regex,
+}
+
+impl Pattern {
+    /// The pattern as it was written, including the slashes and the flags
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn regex(&self) -> &regex::Regex {
+        &self.regex
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for Pattern {}
+
+/// Alternative spellings of terms, from the `[aliases]` table of the configuration
+///
+/// e.g. `cpp = ["c++", "cplusplus"]`, a match of any of these counts as `cpp`
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Aliases(pub BTreeMap<String, Vec<String>>);
+
+impl Aliases {
+    // finds the name a term is known by, the term can be the name or one of its aliases
+    fn resolve(&self, term: &str) -> Option<(&String, &[String])> {
+        self.0
+            .iter()
+            .find(|(name, aliases)| {
+                same_words(name, term) || aliases.iter().any(|alias| same_words(alias, term))
+            })
+            .map(|(name, aliases)| (name, &**aliases))
+    }
+}
+
+fn same_words(left: &str, right: &str) -> bool {
+    let (mut left, mut right) = (left.split_whitespace(), right.split_whitespace());
+    loop {
+        match (left.next(), right.next()) {
+            (Some(l), Some(r)) if l.eq_ignore_ascii_case(r) => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Why a query could not be parsed
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    pub source: String,
+    /// The byte offset of the problem in the source
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use unicode_width::UnicodeWidthStr as _;
+
+        // point at the problem, the source is indented so it stands out
+        let pad = self.source[..self.offset].width();
+        writeln!(f, "invalid query: {}", self.message)?;
+        writeln!(f, "    {}", self.source)?;
+        write!(f, "    {:pad$}^", "", pad = pad)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl Query {
+    /// Parses the query arguments into groups
+    ///
+    /// The expressions are separated by spaces, e.g. `rust AND bevy go` has the groups `rust AND bevy` and `go`.
+    /// An argument with spaces in it, but without any quotes, parentheses or operators, is a phrase
+    ///
+    /// Terms with aliases are known by their name, so the groups for `cpp` and `c++` are merged
+    pub fn parse_args(args: &[String], aliases: &Aliases) -> Result<Vec<Self>, QueryError> {
+        let mut source = String::new();
+        for arg in args {
+            if !source.is_empty() {
+                source.push(' ');
+            }
+            if is_bare_phrase(arg) {
+                let _ = write!(source, "\"{}\"", arg.trim());
+            } else {
+                source.push_str(arg);
+            }
+        }
+        Self::parse(&source, aliases)
+    }
+
+    /// Parses a query into groups, see [`Query::parse_args`]
+    pub fn parse(source: &str, aliases: &Aliases) -> Result<Vec<Self>, QueryError> {
+        let tokens = lex(source)?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            pos: 0,
+            aliases,
+        };
+
+        let mut queries = vec![];
+        while let Some(token) = parser.peek() {
+            if token.kind == Kind::Close {
+                return Err(parser.error(token.offset, "unexpected `)`"));
+            }
+            let expr = parser.parse_or(false)?;
+            let label = expr.to_string();
+            // the same group can be written in different ways
+            if !queries.iter().any(|q: &Self| q.label == label) {
+                queries.push(Self { label, expr });
+            }
+        }
+        Ok(queries)
+    }
+
+    /// The terms that a matching stream could have, the terms under a `NOT` and the patterns aren't included
+    pub fn terms(&self) -> Vec<String> {
+        fn collect(expr: &Expr, out: &mut Vec<String>) {
+            match expr {
+                Expr::Term(term) => out.push(term.clone()),
+                Expr::Phrase(words) => out.push(words.join(" ")),
+                Expr::Aliased { alternatives, .. } => {
+                    out.extend(alternatives.iter().map(|words| words.join(" ")))
+                }
+                Expr::And(left, right) | Expr::Or(left, right) => {
+                    collect(left, out);
+                    collect(right, out);
+                }
+                Expr::Pattern(..) | Expr::Not(..) => {}
+            }
+        }
+
+        let mut terms = vec![];
+        collect(&self.expr, &mut terms);
+        terms
+    }
+
+    // where the query first matched the stream, if it did
+    pub(crate) fn find(&self, text: &Text<'_>) -> Option<Hit> {
+        self.expr.find(text)
+    }
+}
+
+impl Expr {
+    fn find(&self, text: &Text<'_>) -> Option<Hit> {
+        match self {
+            Self::Term(term) => text.find(std::slice::from_ref(term)),
+            Self::Phrase(words) => text.find(words),
+            Self::Pattern(pattern) => text.find_pattern(&pattern.regex),
+            Self::Aliased { alternatives, .. } => alternatives
+                .iter()
+                .filter_map(|words| text.find(words))
+                .min(),
+            Self::And(left, right) => {
+                let (left, right) = (left.find(text)?, right.find(text)?);
+                Some(Hit {
+                    // it is only an exact match if both sides are
+                    kind: left.kind.max(right.kind),
+                    at: left.at.min(right.at),
+                })
+            }
+            Self::Or(left, right) => match (left.find(text), right.find(text)) {
+                (Some(left), Some(right)) => Some(left.min(right)),
+                (left, right) => left.or(right),
+            },
+            Self::Not(expr) => match expr.find(text) {
+                Some(..) => None,
+                None => Some(Hit {
+                    kind: MatchKind::Exact,
+                    at: At::Absence,
+                }),
+            },
+        }
+    }
+
+    // whether this needs parentheses when it is an operand of an `AND` or a `NOT`.
+    // the operators group to the left, so a right operand of the same kind needs them too
+    fn is_or(&self) -> bool {
+        matches!(self, Self::Or(..))
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(self, Self::And(..) | Self::Or(..))
+    }
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn operand(f: &mut std::fmt::Formatter<'_>, expr: &Expr, wrap: bool) -> std::fmt::Result {
+            if wrap {
+                write!(f, "({})", expr)
+            } else {
+                write!(f, "{}", expr)
+            }
+        }
+
+        match self {
+            Self::Term(term) => f.write_str(term),
+            Self::Phrase(words) => write!(f, "\"{}\"", words.join(" ")),
+            Self::Pattern(pattern) => f.write_str(&pattern.text),
+            Self::Aliased { name, .. } if name.contains(char::is_whitespace) => {
+                write!(f, "\"{}\"", name)
+            }
+            Self::Aliased { name, .. } => f.write_str(name),
+            Self::And(left, right) => {
+                operand(f, left, left.is_or())?;
+                match &**right {
+                    Self::Not(right) => {
+                        f.write_str(" NOT ")?;
+                        operand(f, right, right.is_compound())
+                    }
+                    right => {
+                        f.write_str(" AND ")?;
+                        operand(f, right, right.is_compound())
+                    }
+                }
+            }
+            Self::Or(left, right) => {
+                write!(f, "{} OR ", left)?;
+                operand(f, right, right.is_or())
+            }
+            Self::Not(expr) => {
+                f.write_str("NOT ")?;
+                operand(f, expr, expr.is_compound())
+            }
+        }
+    }
+}
+
+/// How a query matched a stream
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// Every term matched a word or a tag exactly, ignoring case
+    Exact,
+    /// A term only matched loosely, e.g. `Rust's` or `rustlang` for `rust`
+    Fuzzy,
+}
+
+/// How and where a query matched a stream
+///
+/// Streams go in the group that matched best, exact matches come first and then the earliest match,
+/// the tags come before the title
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Hit {
+    pub(crate) kind: MatchKind,
+    at: At,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum At {
+    Tag(usize),
+    Title(usize),
+    /// The stream matched because it didn't have something
+    Absence,
+}
+
+/// The parts of a stream that a query is matched against
+pub(crate) struct Text<'a> {
+    tags: &'a [Box<str>],
+    title: &'a str,
+    words: Vec<&'a str>,
+    /// Whether the terms can match loosely if they don't match exactly
+    fuzzy: bool,
+}
+
+impl<'a> Text<'a> {
+    pub(crate) fn new(stream: &'a Stream, fuzzy: bool) -> Self {
+        Self {
+            tags: &stream.tags,
+            title: &stream.title,
+            fuzzy,
+            words: crate::tokenizer::tokenize(&stream.title),
+        }
+    }
+
+    fn find(&self, words: &[String]) -> Option<Hit> {
+        let exact = self.find_with(words, MatchKind::Exact, |left, right| {
+            left.eq_ignore_ascii_case(right)
+        });
+        match exact {
+            None if self.fuzzy => self.find_with(words, MatchKind::Fuzzy, crate::fuzzy::matches),
+            exact => exact,
+        }
+    }
+
+    fn find_with(
+        &self,
+        words: &[String],
+        kind: MatchKind,
+        eq: impl Fn(&str, &str) -> bool,
+    ) -> Option<Hit> {
+        let matches = |parts: &[&str]| {
+            parts.len() == words.len() && words.iter().zip(parts).all(|(l, r)| eq(l, r))
+        };
+
+        // tags can have spaces in them, so a phrase can match a tag
+        let at = match self
+            .tags
+            .iter()
+            .position(|tag| matches(&tag.split(' ').collect::<Vec<_>>()))
+        {
+            Some(n) => At::Tag(n),
+            None => At::Title(self.words.windows(words.len()).position(matches)?),
+        };
+        Some(Hit { kind, at })
+    }
+
+    fn find_pattern(&self, regex: &regex::Regex) -> Option<Hit> {
+        let at = match self.tags.iter().position(|tag| regex.is_match(tag)) {
+            Some(n) => At::Tag(n),
+            None => {
+                // the position is the number of words before the match
+                let found = regex.find(self.title)?;
+                At::Title(self.title[..found.start()].split_whitespace().count())
+            }
+        };
+        Some(Hit {
+            kind: MatchKind::Exact,
+            at,
+        })
+    }
+}
+
+// e.g. `what_stream "game engine"`, where the shell has already removed the quotes
+fn is_bare_phrase(arg: &str) -> bool {
+    arg.trim().contains(char::is_whitespace)
+        && !arg.trim_start().starts_with('/')
+        && !arg.contains(['"', '(', ')'])
+        && !arg
+            .split_whitespace()
+            .any(|word| matches!(word, "AND" | "OR" | "NOT"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Phrase(Vec<String>),
+    Pattern(Pattern),
+}
+
+#[derive(Debug)]
+struct Token {
+    kind: Kind,
+    offset: usize,
+}
+
+fn lex(source: &str) -> Result<Vec<Token>, QueryError> {
+    let error = |offset, message: &str| QueryError {
+        source: source.to_string(),
+        offset,
+        message: message.to_string(),
+    };
+
+    let mut tokens = vec![];
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(offset, ch)) = chars.peek() {
+        let kind = match ch {
+            ch if ch.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                Kind::Open
+            }
+            ')' => {
+                chars.next();
+                Kind::Close
+            }
+            '"' => {
+                chars.next();
+                let start = offset + 1;
+                let end = loop {
+                    match chars.next() {
+                        Some((end, '"')) => break end,
+                        Some(..) => {}
+                        None => return Err(error(offset, "this quote is never closed")),
+                    }
+                };
+                let words = source[start..end]
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                if words.is_empty() {
+                    return Err(error(offset, "this phrase is empty"));
+                }
+                Kind::Phrase(words)
+            }
+            '/' => {
+                chars.next();
+                // a slash in the pattern is escaped, e.g. `/rust\/wasm/`
+                let end = loop {
+                    match chars.next() {
+                        Some((_, '\\')) => {
+                            chars.next();
+                        }
+                        Some((end, '/')) => break end,
+                        Some(..) => {}
+                        None => return Err(error(offset, "this pattern is never closed")),
+                    }
+                };
+                let mut flags_end = source.len();
+                while let Some(&(i, ch)) = chars.peek() {
+                    if !ch.is_alphanumeric() {
+                        flags_end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                Kind::Pattern(compile(source, offset, end, flags_end)?)
+            }
+            _ => {
+                let mut end = source.len();
+                while let Some(&(i, ch)) = chars.peek() {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                // the operators have to be uppercase, so the plain words can still be searched for
+                match &source[offset..end] {
+                    "AND" => Kind::And,
+                    "OR" => Kind::Or,
+                    "NOT" => Kind::Not,
+                    word => Kind::Word(word.to_string()),
+                }
+            }
+        };
+        tokens.push(Token { kind, offset });
+    }
+    Ok(tokens)
+}
+
+// compiles the pattern between the slashes at `start` and `end`, with the flags up to `flags_end`
+fn compile(
+    source: &str,
+    start: usize,
+    end: usize,
+    flags_end: usize,
+) -> Result<Pattern, QueryError> {
+    let error = |offset, message: String| QueryError {
+        source: source.to_string(),
+        offset,
+        message,
+    };
+
+    let pattern = &source[start + 1..end];
+    let mut syntax = regex_syntax::ParserBuilder::new();
+    let mut builder = regex::RegexBuilder::new(pattern);
+    for (i, flag) in source[end + 1..flags_end].char_indices() {
+        match flag {
+            'i' => {
+                syntax.case_insensitive(true);
+                builder.case_insensitive(true);
+            }
+            'm' => {
+                syntax.multi_line(true);
+                builder.multi_line(true);
+            }
+            's' => {
+                syntax.dot_matches_new_line(true);
+                builder.dot_matches_new_line(true);
+            }
+            'x' => {
+                syntax.ignore_whitespace(true);
+                builder.ignore_whitespace(true);
+            }
+            'U' => {
+                syntax.swap_greed(true);
+                builder.swap_greed(true);
+            }
+            _ => {
+                let message = format!(
+                    "unknown flag `{}`, expected `i`, `m`, `s`, `x` or `U`",
+                    flag
+                );
+                return Err(error(end + 1 + i, message));
+            }
+        }
+    }
+
+    // the regex crate's errors don't say where the problem is, but the parser's do
+    if let Err(err) = syntax.build().parse(pattern) {
+        let (offset, message) = match &err {
+            regex_syntax::Error::Parse(err) => (err.span().start.offset, err.kind().to_string()),
+            regex_syntax::Error::Translate(err) => {
+                (err.span().start.offset, err.kind().to_string())
+            }
+            err => (0, err.to_string()),
+        };
+        return Err(error(
+            start + 1 + offset,
+            format!("invalid pattern: {}", message),
+        ));
+    }
+
+    let regex = builder
+        .build()
+        .map_err(|err| error(start, format!("invalid pattern: {}", err)))?;
+
+    Ok(Pattern {
+        text: source[start..flags_end].to_string(),
+        regex,
+    })
+}
+
+// a recursive descent parser, `AND` and `NOT` bind tighter than `OR`
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    aliases: &'a Aliases,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_kind(&self) -> Option<&Kind> {
+        self.peek().map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, offset: usize, message: &str) -> QueryError {
+        QueryError {
+            source: self.source.to_string(),
+            offset,
+            message: message.to_string(),
+        }
+    }
+
+    // at the top level, a term after a complete expression starts the next group.
+    // but inside parentheses it is the same as an `OR`
+    fn parse_or(&mut self, nested: bool) -> Result<Expr, QueryError> {
+        let mut left = self.parse_and()?;
+        loop {
+            match self.peek_kind() {
+                Some(Kind::Or) => {
+                    self.next();
+                }
+                Some(Kind::Word(..) | Kind::Phrase(..) | Kind::Pattern(..) | Kind::Open)
+                    if nested => {}
+                _ => break,
+            }
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // `a NOT b` is the same as `a AND NOT b`
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            left = match self.peek_kind() {
+                Some(Kind::And) => {
+                    self.next();
+                    Expr::And(Box::new(left), Box::new(self.parse_unary()?))
+                }
+                Some(Kind::Not) => {
+                    self.next();
+                    let right = Expr::Not(Box::new(self.parse_unary()?));
+                    Expr::And(Box::new(left), Box::new(right))
+                }
+                _ => break,
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if let Some(Kind::Not) = self.peek_kind() {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    // a word or a phrase, which is known by its name if it has aliases
+    fn term(&self, mut words: Vec<String>) -> Expr {
+        let (name, aliases) = match self.aliases.resolve(&words.join(" ")) {
+            Some(found) => found,
+            None if words.len() == 1 => return Expr::Term(words.remove(0)),
+            None => return Expr::Phrase(words),
+        };
+
+        let alternatives = std::iter::once(name)
+            .chain(aliases)
+            .map(|s| s.split_whitespace().map(String::from).collect::<Vec<_>>())
+            .filter(|words| !words.is_empty())
+            .collect();
+        Expr::Aliased {
+            name: name.split_whitespace().collect::<Vec<_>>().join(" "),
+            alternatives,
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, QueryError> {
+        let end = self.source.len();
+        let (kind, offset) = match self.next() {
+            Some(token) => (token.kind.clone(), token.offset),
+            None => return Err(self.error(end, "expected a term")),
+        };
+
+        match kind {
+            Kind::Word(word) => Ok(self.term(vec![word])),
+            Kind::Phrase(words) => Ok(self.term(words)),
+            Kind::Pattern(pattern) => Ok(Expr::Pattern(pattern)),
+            Kind::Open => {
+                let expr = self.parse_or(true)?;
+                match self.next() {
+                    Some(Token {
+                        kind: Kind::Close, ..
+                    }) => Ok(expr),
+                    _ => Err(self.error(offset, "this parenthesis is never closed")),
+                }
+            }
+            _ => Err(self.error(offset, "expected a term")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(s: &str) -> Expr {
+        Expr::Term(s.to_string())
+    }
+
+    fn phrase(s: &str) -> Expr {
+        Expr::Phrase(s.split_whitespace().map(String::from).collect())
+    }
+
+    fn and(left: Expr, right: Expr) -> Expr {
+        Expr::And(Box::new(left), Box::new(right))
+    }
+
+    fn or(left: Expr, right: Expr) -> Expr {
+        Expr::Or(Box::new(left), Box::new(right))
+    }
+
+    fn not(expr: Expr) -> Expr {
+        Expr::Not(Box::new(expr))
+    }
+
+    fn exprs(source: &str) -> Vec<Expr> {
+        Query::parse(source, &Aliases::default())
+            .unwrap_or_else(|err| panic!("{}", err))
+            .into_iter()
+            .map(|query| query.expr)
+            .collect()
+    }
+
+    #[test]
+    fn groups() {
+        let queries: &[(&str, &[Expr])] = &[
+            ("rust", &[term("rust")]),
+            (
+                r#"rust AND (bevy OR wgpu) NOT "just chatting""#,
+                &[and(
+                    and(term("rust"), or(term("bevy"), term("wgpu"))),
+                    not(phrase("just chatting")),
+                )],
+            ),
+            ("a b", &[term("a"), term("b")]),
+            ("(a b)", &[or(term("a"), term("b"))]),
+            ("a OR b c", &[or(term("a"), term("b")), term("c")]),
+            ("a AND b OR c", &[or(and(term("a"), term("b")), term("c"))]),
+            ("a OR b AND c", &[or(term("a"), and(term("b"), term("c")))]),
+            ("a NOT b", &[and(term("a"), not(term("b")))]),
+            ("NOT a b", &[not(term("a")), term("b")]),
+            ("NOT NOT a", &[not(not(term("a")))]),
+            ("a OR (b OR c)", &[or(term("a"), or(term("b"), term("c")))]),
+            ("rust rust", &[term("rust")]),
+            ("and or not", &[term("and"), term("or"), term("not")]),
+            (r#""game   dev""#, &[phrase("game dev")]),
+        ];
+
+        for (source, expected) in queries {
+            assert_eq!(exprs(source), *expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn args() {
+        let args: &[(&[&str], &[Expr])] = &[
+            (&["rust", "bevy"], &[term("rust"), term("bevy")]),
+            (&["game dev"], &[phrase("game dev")]),
+            (&["rust AND bevy"], &[and(term("rust"), term("bevy"))]),
+            (&["(rust", "bevy)"], &[or(term("rust"), term("bevy"))]),
+        ];
+
+        for (args, expected) in args {
+            let args = args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            let exprs = Query::parse_args(&args, &Aliases::default())
+                .unwrap()
+                .into_iter()
+                .map(|query| query.expr)
+                .collect::<Vec<_>>();
+            assert_eq!(exprs, *expected, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn labels() {
+        let aliases = Aliases(
+            [("cpp".to_string(), vec!["c++".to_string()])]
+                .into_iter()
+                .collect(),
+        );
+
+        let queries = [
+            "rust",
+            r#""game dev""#,
+            r#"rust AND (bevy OR wgpu) NOT "just chatting""#,
+            "a OR b AND c",
+            "(a OR b) AND c",
+            "a OR (b OR c)",
+            "a AND (b AND c)",
+            "NOT (a OR b) AND c",
+            "a NOT (b AND c)",
+            "NOT NOT a",
+            "(a b) c",
+            "/advent ?of ?code/i OR aoc",
+            r"(/rust\/wasm/ OR bevy) NOT /(?i)just chatting/",
+            "c++ AND NOT cpp",
+        ];
+
+        for source in queries {
+            for query in Query::parse(source, &aliases).unwrap() {
+                let reparsed = Query::parse(&query.label, &aliases).unwrap();
+                assert_eq!(
+                    reparsed,
+                    std::slice::from_ref(&query),
+                    "{} -> {}",
+                    source,
+                    query.label
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let queries = [
+            ("(rust", 0),
+            ("rust (bevy OR wgpu", 5),
+            ("((a) OR b", 0),
+            (r#""just chatting"#, 0),
+            (r#"rust "game dev"#, 5),
+            (r#""""#, 0),
+            ("rust)", 4),
+            ("rust AND", 8),
+            ("NOT", 3),
+            ("rust OR OR bevy", 8),
+            ("()", 1),
+            ("/rust(/i", 5),
+            ("rust /a[/", 7),
+            ("/x/q", 3),
+            ("/x/iq", 4),
+            ("/rust", 0),
+            ("/(?z)rust/", 3),
+        ];
+
+        for (source, offset) in queries {
+            let err = Query::parse(source, &Aliases::default()).unwrap_err();
+            assert_eq!(err.offset, offset, "{}: {}", source, err.message);
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        let patterns = [
+            ("/advent ?of ?code/i", "Advent of Code day 3", true),
+            ("/advent ?of ?code/", "Advent of Code day 3", false),
+            (r"/rust\/wasm/", "rust/wasm game jam", true),
+            ("/^rust/", "learning rust", false),
+            ("/r u s t/x", "rust", true),
+        ];
+
+        for (source, title, expected) in patterns {
+            let expr = exprs(source).remove(0);
+            let pattern = match &expr {
+                Expr::Pattern(pattern) => pattern,
+                expr => panic!("{} is not a pattern: {:?}", source, expr),
+            };
+            assert_eq!(pattern.as_str(), source);
+            assert_eq!(pattern.regex().is_match(title), expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn caret() {
+        let err = Query::parse("rust (bevy", &Aliases::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid query: this parenthesis is never closed\n    rust (bevy\n         ^"
+        );
+
+        let err = Query::parse("/x/q", &Aliases::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid query: unknown flag `q`, expected `i`, `m`, `s`, `x` or `U`\n    /x/q\n       ^"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+
+use crate::category::Category;
+
+use super::{Render, Style, Theme};
+
+pub struct Categories<'a> {
+    pub query: &'a str,
+    pub categories: &'a [Category],
+}
+
+impl<'a> Render for Categories<'a> {
+    fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()> {
+        writeln!(
+            writer,
+            "{left}{query}",
+            query = theme.category.paint(&self.query),
+            left = theme.fringe.paint(&*style.top)
+        )?;
+
+        if self.categories.is_empty() {
+            writeln!(
+                writer,
+                "{left}no categories found for {query}",
+                query = theme.category.paint(&self.query),
+                left = theme.fringe.paint(&*style.end)
+            )?;
+        }
+
+        for (n, category) in self.categories.iter().enumerate() {
+            if n > 0 {
+                writeln!(writer, "{}", theme.entry.paint(&*style.entry_sep))?;
+            }
+
+            let end = if n < self.categories.len() - 1 {
+                &*style.stats
+            } else {
+                &*style.end
+            };
+
+            writeln!(
+                writer,
+                "{left}[{id}] {name}",
+                id = theme.spoken_language.paint(&category.id),
+                name = theme.title.paint(&category.name),
+                left = theme.fringe.paint(&*style.title),
+            )?;
+
+            writeln!(
+                writer,
+                "{left}{url}",
+                url = theme.link.paint(&category.box_art_url),
+                left = theme.fringe.paint(end),
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -18,14 +18,18 @@ impl Demo {
                     user_id: Box::from("12345"),
                     viewer_count: 7,
                     language: Box::from("en"),
-                    tag_ids: Box::from([Box::from("rust")]),
-                    user_tag_map: {
-                        std::iter::once((
-                            "b23d8969-29f8-4f75-ad44-2772e3d28ec4", "Rust"
-                        )).map(|(k,v)| (Box::from(k), Box::from(v))).collect()
-                    },
+                    tags: Box::from([Box::from("Rust"), Box::from("English")]),
+                    tag_ids: Box::from([]),
                     uptime: 0,
+                    display_name: Box::from("A_Rustacean"),
+                    broadcaster_type: Box::from("partner"),
+                    followers: Some(1234),
+                    game_name: Box::from("Software and Game Development"),
+                    ..Default::default()
                 }],
+                display_names: true,
+                badges: true,
+                categories: true,
             },
             Entries {
                 query: "c++",
@@ -37,13 +41,12 @@ impl Demo {
                         user_id: Box::from("12346"),
                         viewer_count: 1,
                         language: Box::from("en"),
-                        tag_ids: Box::from([Box::from("c++")]),
-                        user_tag_map: {
-                               std::iter::once((
-                            "2b194c54-bc5f-4c4c-85f7-32f03ab0fd7c", "C++"
-                        )).map(|(k,v)| (Box::from(k), Box::from(v))).collect()
-                        },
+                        tags: Box::from([Box::from("C++")]),
+                        tag_ids: Box::from([]),
                         uptime: 0,
+                        display_name: Box::from("ある開発者"),
+                        broadcaster_type: Box::from("affiliate"),
+                        ..Default::default()
                     },
                     Stream {
                         started_at: Box::from("25 minutes"),
@@ -52,15 +55,15 @@ impl Demo {
                         user_id: Box::from("12347"),
                         viewer_count: 2,
                         language: Box::from("fr"),
-                        tag_ids: Box::from([Box::from("")]),
-                        user_tag_map: {
-                               std::iter::once((
-                            "2b194c54-bc5f-4c4c-85f7-32f03ab0fd7c", "C++"
-                        )).map(|(k,v)| (Box::from(k), Box::from(v))).collect()
-                        },
+                        tags: Box::from([Box::from("C++"), Box::from("Français")]),
+                        tag_ids: Box::from([]),
                         uptime: 0,
+                        ..Default::default()
                     },
                 ],
+                display_names: true,
+                badges: true,
+                categories: true,
             },
         ]
             .into_iter()
@@ -0,0 +1,111 @@
+use std::io::Write;
+
+use crate::channel::ChannelDetails;
+
+use super::{local_time, Render, Style, Theme, DATE_FORMAT};
+
+/// Everything about one channel, whether it is live or not
+pub struct Details<'a> {
+    pub details: &'a ChannelDetails,
+}
+
+impl<'a> Render for Details<'a> {
+    fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()> {
+        let ChannelDetails {
+            user,
+            stream,
+            channel,
+            last_broadcast,
+        } = self.details;
+
+        writeln!(
+            writer,
+            "{left}{login} ({state})",
+            login = theme.category.paint(&user.login),
+            state = if stream.is_some() { "live" } else { "offline" },
+            left = theme.fringe.paint(&*style.top)
+        )?;
+
+        // a live stream is more up to date than the channel information
+        let (title, language, game_name, tags) = match (stream, channel) {
+            (Some(stream), _) => (
+                &stream.title,
+                &stream.language,
+                &stream.game_name,
+                &stream.tags,
+            ),
+            (None, Some(channel)) => (
+                &channel.title,
+                &channel.broadcaster_language,
+                &channel.game_name,
+                &channel.tags,
+            ),
+            (None, None) => return Ok(()),
+        };
+
+        let mut lines = vec![];
+
+        let mut link = format!(
+            "[{language}] https://twitch.tv/{link}",
+            language = theme.spoken_language.paint(&language.to_ascii_uppercase()),
+            link = theme.link.paint(&user.login),
+        );
+        if !user.display_name.eq_ignore_ascii_case(&user.login) {
+            link += &format!(" ({})", theme.link.paint(&user.display_name));
+        }
+        if !user.broadcaster_type.is_empty() {
+            link += &format!(" [{}]", theme.badge.paint(&user.broadcaster_type));
+        }
+        lines.push(link);
+
+        lines.push(if title.trim().is_empty() {
+            "untitled".to_string()
+        } else {
+            theme.title.paint(title.trim()).to_string()
+        });
+
+        let mut stats = match (stream, last_broadcast) {
+            (Some(stream), _) => format!(
+                "started {uptime} ago, {viewers} watching",
+                uptime = theme.uptime.paint(&stream.started_at),
+                viewers = theme.viewers.paint(&stream.viewer_count),
+            ),
+            (None, Some(video)) => match local_time(&video.created_at) {
+                Some(time) => format!(
+                    "last streamed {}",
+                    theme.uptime.paint(time.format(DATE_FORMAT))
+                ),
+                None => "last streamed at an unknown time".to_string(),
+            },
+            (None, None) => "no past broadcasts".to_string(),
+        };
+        if !game_name.is_empty() {
+            stats += &format!(", in {}", theme.category.paint(game_name));
+        }
+        lines.push(stats);
+
+        if !tags.is_empty() {
+            let tags = tags
+                .iter()
+                .map(|tag| theme.tag.paint(tag).to_string())
+                .collect::<Vec<_>>();
+            lines.push(format!("tags: {}", tags.join(" | ")));
+        }
+
+        if !user.description.trim().is_empty() {
+            lines.push(user.description.trim().to_string());
+        }
+
+        for (n, line) in lines.iter().enumerate() {
+            let left = match n {
+                _ if n == lines.len() - 1 => &*style.end,
+                0 => &*style.link,
+                1 => &*style.title,
+                _ => &*style.stats,
+            };
+            writeln!(writer, "{}{}", theme.fringe.paint(left), line)?;
+        }
+
+        Ok(())
+    }
+}
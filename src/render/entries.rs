@@ -7,6 +7,12 @@ use super::{Render, Style, Theme};
 pub struct Entries<'a> {
     pub query: &'a str,
     pub streams: &'a [Stream],
+    /// Use the display name of the user, rather than their login
+    pub display_names: bool,
+    /// Show whether the user is a partner or an affiliate
+    pub badges: bool,
+    /// Show the category the stream is in
+    pub categories: bool,
 }
 
 impl<'a> Render for Entries<'a> {
@@ -28,16 +34,38 @@ impl<'a> Render for Entries<'a> {
                 writeln!(writer, "{}", theme.entry.paint(&*style.entry_sep))?;
             }
 
-            writeln!(
+            let name = if self.display_names {
+                stream.name()
+            } else {
+                &stream.user_name
+            };
+
+            write!(
                 writer,
                 "{left}[{language}] https://twitch.tv/{link}",
                 language = theme
                     .spoken_language
                     .paint(&stream.language.to_ascii_uppercase()),
-                link = theme.link.paint(&stream.user_name),
+                link = theme.link.paint(name),
                 left = theme.fringe.paint(&*style.link),
             )?;
 
+            // names in other scripts can't be used in the link, so show them next to it
+            if let Some(localized) = stream.localized_name().filter(|_| self.display_names) {
+                write!(writer, " ({})", theme.link.paint(localized))?;
+            }
+
+            if self.badges && !stream.broadcaster_type.is_empty() {
+                write!(writer, " [{}]", theme.badge.paint(&stream.broadcaster_type))?;
+            }
+
+            // the query didn't match exactly, so the stream might not be what was searched for
+            if stream.matched == Some(crate::MatchKind::Fuzzy) {
+                write!(writer, " [{}]", theme.badge.paint("fuzzy"))?;
+            }
+
+            writeln!(writer)?;
+
             write!(writer, "{left}", left = theme.fringe.paint(&*style.title))?;
 
             let title = stream.title.trim();
@@ -67,14 +95,14 @@ impl<'a> Render for Entries<'a> {
                 writeln!(writer, "{}", theme.title.paint(&stream.title))?;
             }
 
-            let print_tags = !stream.user_tag_map.is_empty();
+            let print_tags = !stream.tags.is_empty();
             let end = if n < self.streams.len() - 1 {
                 &*style.stats
             } else {
                 &*style.end
             };
 
-            writeln!(
+            write!(
                 writer,
                 "{left}started {uptime} ago, {viewers} watching",
                 uptime = theme.uptime.paint(&stream.started_at),
@@ -84,16 +112,27 @@ impl<'a> Render for Entries<'a> {
                     .paint(if print_tags { &*style.stats } else { end })
             )?;
 
+            if let Some(followers) = stream.followers {
+                write!(
+                    writer,
+                    ", {followers} follower{plural}",
+                    followers = theme.viewers.paint(&followers),
+                    plural = if followers == 1 { "" } else { "s" }
+                )?;
+            }
+
+            if self.categories && !stream.game_name.is_empty() {
+                write!(writer, ", in {}", theme.category.paint(&stream.game_name))?;
+            }
+            writeln!(writer)?;
+
             if !print_tags {
                 continue;
             }
 
             write!(writer, "{left}tags: ", left = theme.fringe.paint(end))?;
-            let len = stream.user_tag_map.len();
-            let mut tags = stream.user_tag_map.values().collect::<Vec<_>>();
-            tags.sort_unstable();
-
-            for (i, tags) in tags.into_iter().enumerate() {
+            let len = stream.tags.len();
+            for (i, tags) in stream.tags.iter().enumerate() {
                 write!(
                     writer,
                     "{}{}",
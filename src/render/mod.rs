@@ -12,6 +12,21 @@ pub use style::Style;
 mod entries;
 pub use entries::Entries;
 
+mod categories;
+pub use categories::Categories;
+
+mod offline;
+pub use offline::Offline;
+
+mod upcoming;
+pub use upcoming::Upcoming;
+
+mod popularity;
+pub use popularity::Popularity;
+
+mod details;
+pub use details::Details;
+
 mod demo;
 pub use demo::Demo;
 
@@ -19,6 +34,17 @@ pub trait Render {
     fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()>;
 }
 
+// e.g. 'Mon 13 Jan 18:00'
+const DATE_FORMAT: &str = "%a %-d %b %H:%M";
+
+// converts a RFC3339 UTC timestamp to local time
+fn local_time(timestamp: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    timestamp
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .ok()
+        .map(|time| time.with_timezone(&chrono::Local))
+}
+
 fn width() -> usize {
     terminal_size::terminal_size()
         .map(|(terminal_size::Width(width), _)| width)
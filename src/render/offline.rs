@@ -0,0 +1,37 @@
+use std::io::Write;
+
+use super::{Render, Style, Theme};
+
+/// A list of channels that aren't live
+pub struct Offline<'a> {
+    pub query: &'a str,
+    pub logins: &'a [Box<str>],
+}
+
+impl<'a> Render for Offline<'a> {
+    fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()> {
+        writeln!(
+            writer,
+            "{left}{query}",
+            query = theme.category.paint(&self.query),
+            left = theme.fringe.paint(&*style.top)
+        )?;
+
+        for (n, login) in self.logins.iter().enumerate() {
+            let left = if n < self.logins.len() - 1 {
+                &*style.link
+            } else {
+                &*style.end
+            };
+
+            writeln!(
+                writer,
+                "{left}https://twitch.tv/{link}",
+                link = theme.link.paint(login),
+                left = theme.fringe.paint(left),
+            )?;
+        }
+
+        Ok(())
+    }
+}
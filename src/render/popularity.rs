@@ -0,0 +1,56 @@
+use std::io::Write;
+
+use crate::top::CategoryStats;
+
+use super::{Render, Style, Theme};
+
+/// How many streams and viewers each category has
+pub struct Popularity<'a> {
+    pub query: &'a str,
+    pub categories: &'a [CategoryStats],
+}
+
+impl<'a> Render for Popularity<'a> {
+    fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()> {
+        writeln!(
+            writer,
+            "{left}{query}",
+            query = theme.category.paint(&self.query),
+            left = theme.fringe.paint(&*style.top)
+        )?;
+
+        for (n, category) in self.categories.iter().enumerate() {
+            if n > 0 {
+                writeln!(writer, "{}", theme.entry.paint(&*style.entry_sep))?;
+            }
+
+            let end = if n < self.categories.len() - 1 {
+                &*style.stats
+            } else {
+                &*style.end
+            };
+
+            writeln!(
+                writer,
+                "{left}[{id}] {name}",
+                id = theme.spoken_language.paint(&category.id),
+                name = theme.title.paint(&category.name),
+                left = theme.fringe.paint(&*style.title),
+            )?;
+
+            // if not every page was counted, there are at least this many
+            let more = if category.complete { "" } else { "+" };
+            writeln!(
+                writer,
+                "{left}{streams}{more} stream{plural}, {viewers}{more} watching",
+                streams = theme.uptime.paint(&category.streams),
+                viewers = theme.viewers.paint(&category.viewers),
+                plural = if category.streams == 1 { "" } else { "s" },
+                more = more,
+                left = theme.fringe.paint(end),
+            )?;
+        }
+
+        Ok(())
+    }
+}
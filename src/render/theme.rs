@@ -12,7 +12,11 @@ pub struct Theme {
     pub uptime: ColorStyle,
     pub viewers: ColorStyle,
 
+    #[serde(alias = "tags")]
     pub tag: ColorStyle,
+
+    #[serde(default = "Theme::default_badge")]
+    pub badge: ColorStyle,
 }
 
 impl Theme {
@@ -29,8 +33,14 @@ impl Theme {
             viewers: ColorStyle::new(Color::CYAN),
 
             tag: ColorStyle::new(Color(64, 64, 64)),
+
+            badge: Self::default_badge(),
         }
     }
+
+    fn default_badge() -> ColorStyle {
+        ColorStyle::new(Color::BRIGHT_MAGENTA)
+    }
 }
 
 impl Default for Theme {
@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use chrono::Local;
+
+use crate::schedule::Schedule;
+
+use super::{local_time, Render, Style, Theme, DATE_FORMAT};
+
+/// The upcoming streams of a channel, in local time
+pub struct Upcoming<'a> {
+    pub schedule: &'a Schedule,
+}
+
+impl<'a> Render for Upcoming<'a> {
+    fn render(&self, writer: &mut dyn Write, style: &Style, theme: &Theme) -> anyhow::Result<()> {
+        let schedule = self.schedule;
+
+        writeln!(
+            writer,
+            "{left}{login}",
+            login = theme.category.paint(&schedule.broadcaster_login),
+            left = theme.fringe.paint(&*style.top)
+        )?;
+
+        let vacation = schedule.vacation.as_ref().and_then(|vacation| {
+            let end = local_time(&vacation.end_time)?;
+            (end > Local::now()).then_some(end)
+        });
+
+        if let Some(end) = vacation {
+            let left = if schedule.segments.is_empty() {
+                &*style.end
+            } else {
+                &*style.link
+            };
+            writeln!(
+                writer,
+                "{left}on vacation until {end}",
+                end = theme.badge.paint(end.format(DATE_FORMAT)),
+                left = theme.fringe.paint(left),
+            )?;
+        } else if schedule.segments.is_empty() {
+            writeln!(
+                writer,
+                "{left}no upcoming streams",
+                left = theme.fringe.paint(&*style.end)
+            )?;
+        }
+
+        for (n, segment) in schedule.segments.iter().enumerate() {
+            if n > 0 || vacation.is_some() {
+                writeln!(writer, "{}", theme.entry.paint(&*style.entry_sep))?;
+            }
+
+            let end = if n < schedule.segments.len() - 1 {
+                &*style.stats
+            } else {
+                &*style.end
+            };
+
+            let start = local_time(&segment.start_time);
+            let when = match (start, segment.end_time.as_deref().and_then(local_time)) {
+                (Some(start), Some(end))
+                    if start.naive_local().date() == end.naive_local().date() =>
+                {
+                    format!(
+                        "{} - {}",
+                        start.format(DATE_FORMAT),
+                        end.format(TIME_FORMAT)
+                    )
+                }
+                (Some(start), Some(end)) => {
+                    format!(
+                        "{} - {}",
+                        start.format(DATE_FORMAT),
+                        end.format(DATE_FORMAT)
+                    )
+                }
+                (Some(start), None) => start.format(DATE_FORMAT).to_string(),
+                (None, _) => segment.start_time.to_string(),
+            };
+
+            write!(
+                writer,
+                "{left}{when}",
+                when = theme.uptime.paint(when),
+                left = theme.fringe.paint(&*style.link),
+            )?;
+            if segment.canceled_until.is_some() {
+                write!(writer, " [{}]", theme.badge.paint("canceled"))?;
+            }
+            writeln!(writer)?;
+
+            let title = match segment.title.trim() {
+                "" => "untitled",
+                title => title,
+            };
+
+            let category = segment.category.as_ref();
+            writeln!(
+                writer,
+                "{left}{title}",
+                title = theme.title.paint(title),
+                left = theme.fringe.paint(if category.is_some() {
+                    &*style.title
+                } else {
+                    end
+                }),
+            )?;
+
+            if let Some(category) = category {
+                writeln!(
+                    writer,
+                    "{left}in {category}",
+                    category = theme.tag.paint(&category.name),
+                    left = theme.fringe.paint(end),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const TIME_FORMAT: &str = "%H:%M";
@@ -0,0 +1,136 @@
+use crate::{
+    helix::{is_not_found, is_unauthorized, Data},
+    Client, Warning,
+};
+
+// How far ahead the upcoming streams are listed, in days
+const UPCOMING_DAYS: i64 = 7;
+
+/// The broadcast schedule of a channel, from `helix/schedule`
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Schedule {
+    pub broadcaster_id: Box<str>,
+    pub broadcaster_login: Box<str>,
+    pub broadcaster_name: Box<str>,
+    /// The upcoming streams, in the order they start
+    #[serde(default, deserialize_with = "crate::stream::nullable")]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub vacation: Option<Vacation>,
+}
+
+/// A scheduled stream
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Segment {
+    pub id: Box<str>,
+    /// When the stream starts, as a RFC3339 UTC timestamp
+    pub start_time: Box<str>,
+    #[serde(default)]
+    pub end_time: Option<Box<str>>,
+    pub title: Box<str>,
+    /// If this is set, the stream was canceled
+    #[serde(default)]
+    pub canceled_until: Option<Box<str>>,
+    #[serde(default)]
+    pub category: Option<SegmentCategory>,
+    #[serde(default)]
+    pub is_recurring: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct SegmentCategory {
+    pub id: Box<str>,
+    pub name: Box<str>,
+}
+
+/// When the broadcaster is on vacation, as RFC3339 UTC timestamps
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Vacation {
+    pub start_time: Box<str>,
+    pub end_time: Box<str>,
+}
+
+/// The schedules of a set of channels, and any problems encountered while fetching them
+#[derive(Debug, Default)]
+pub struct Schedules {
+    pub schedules: Vec<Schedule>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Fetches the upcoming streams for each login
+///
+/// Channels without a schedule have no segments. Logins that don't belong to a user,
+/// and schedules that could not be fetched, are reported as [`Warning`]s
+pub fn fetch_schedules(client: &mut Client, logins: &[String]) -> anyhow::Result<Schedules> {
+    client.retry_unauthorized(|client| try_fetch_schedules(client, logins))
+}
+
+fn try_fetch_schedules(client: &Client, logins: &[String]) -> anyhow::Result<Schedules> {
+    let mut schedules = Schedules::default();
+
+    for logins in logins.chunks(100) {
+        let users = crate::get_users(client, "login", logins.iter().map(|s| &**s))?;
+
+        let mut unknown = vec![];
+        for login in logins {
+            let user = match users
+                .values()
+                .find(|user| user.login.eq_ignore_ascii_case(login))
+            {
+                Some(user) => user,
+                None => {
+                    unknown.push(login.as_str().into());
+                    continue;
+                }
+            };
+
+            let schedule = match get_schedule(client, &user.id) {
+                Ok(Some(schedule)) => schedule,
+                // they haven't made a schedule
+                Ok(None) => Schedule {
+                    broadcaster_id: user.id.clone(),
+                    broadcaster_login: user.login.clone(),
+                    broadcaster_name: user.display_name.clone(),
+                    ..Default::default()
+                },
+                Err(err) if is_unauthorized(&err) => return Err(err),
+                Err(err) => {
+                    schedules
+                        .warnings
+                        .push(Warning::schedule(&user.login, &err));
+                    continue;
+                }
+            };
+            schedules.schedules.push(schedule);
+        }
+
+        if !unknown.is_empty() {
+            schedules.warnings.push(Warning::unknown_logins(&unknown));
+        }
+    }
+
+    Ok(schedules)
+}
+
+/// Gets the upcoming streams of a broadcaster, this is `None` if they don't have a schedule
+// https://dev.twitch.tv/docs/api/reference/#get-channel-stream-schedule
+pub fn get_schedule(client: &Client, broadcaster_id: &str) -> anyhow::Result<Option<Schedule>> {
+    let resp = match client.get_json::<Data<Schedule>>(
+        "schedule",
+        &[("broadcaster_id", broadcaster_id), ("first", "25")],
+    ) {
+        Ok(resp) => resp,
+        Err(err) if is_not_found(&err) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let mut schedule = resp.data;
+    let horizon = chrono::Utc::now() + chrono::Duration::days(UPCOMING_DAYS);
+    schedule.segments.retain(|segment| {
+        segment
+            .start_time
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .is_ok_and(|start| start <= horizon)
+    });
+    Ok(Some(schedule))
+}
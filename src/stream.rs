@@ -1,71 +1,310 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Mutex},
+};
 
 use crate::{
-    args::{AppAccess, Column, Direction, SortAction},
-    config::TagCache,
-    SCIENCE_AND_TECH_CATEGORY, SOFTWARE_AND_GAME_DEV_CATEGORY, WHAT_STREAM_CLIENT_ID,
+    args::{Column, Direction, SortAction},
+    config::{CategoryCache, FollowerCache, TagCache},
+    helix::{is_bad_request, is_unauthorized, Data},
+    pool::map_shared,
+    query::{Hit, Text},
+    user::User,
+    Client, Excluded, Filter, MatchKind, Query, Warning,
 };
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct Stream {
     pub started_at: Box<str>,
     pub title: Box<str>,
+    /// The login of the user, this is used for links
+    // helix's `user_name` is the display name, it can't be used in a link if it is localized
+    #[serde(rename(deserialize = "user_login"))]
     pub user_name: Box<str>,
     pub user_id: Box<str>,
     pub viewer_count: i64,
     pub language: Box<str>,
 
+    /// The category the stream is in
+    #[serde(default)]
+    pub game_id: Box<str>,
+    #[serde(default)]
+    pub game_name: Box<str>,
+
+    /// Either `live`, or empty if there was an error
+    #[serde(rename = "type", default)]
+    pub kind: Box<str>,
+    #[serde(default)]
+    pub is_mature: bool,
+    /// The content classification labels of the channel, these are only looked up if they're filtered by
+    #[serde(default, deserialize_with = "nullable")]
+    pub content_classification_labels: Box<[Box<str>]>,
+
+    /// The freeform tags set by the broadcaster
+    #[serde(default, deserialize_with = "nullable")]
+    pub tags: Box<[Box<str>]>,
+
+    /// The ids of Twitch's old tag system, these are resolved with the [`TagCache`]
+    #[serde(default, deserialize_with = "nullable", skip_serializing)]
     pub tag_ids: Box<[Box<str>]>,
 
-    #[serde(skip_deserializing)]
-    pub user_tag_map: HashMap<Box<str>, Box<str>>,
-
     #[serde(skip_deserializing)]
     pub uptime: i64,
+
+    /// The localized name of the user, `user_name` is their login
+    #[serde(rename(deserialize = "user_name"), default)]
+    pub display_name: Box<str>,
+    #[serde(default)]
+    pub description: Box<str>,
+    /// Either `partner`, `affiliate` or empty
+    #[serde(default)]
+    pub broadcaster_type: Box<str>,
+    #[serde(default)]
+    pub profile_image_url: Box<str>,
+
+    /// How many followers the user has, this is only fetched if it was asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub followers: Option<u64>,
+
+    /// How the query matched the stream, this isn't set for the watchlist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched: Option<MatchKind>,
 }
 
-pub fn fetch_streams<'a>(
-    query: &'a [String],
-    languages: &[String],
-    app_access: &AppAccess,
-    tag_cache: &mut TagCache,
-) -> anyhow::Result<Vec<(&'a String, Stream)>> {
-    let agent = ureq::agent();
-    let token = format!("Bearer {}", app_access.access_token);
+impl Stream {
+    /// Copies the profile of the user onto the stream
+    pub fn set_user(&mut self, user: &User) {
+        self.user_name = user.login.clone();
+        self.display_name = user.display_name.clone();
+        self.description = user.description.clone();
+        self.broadcaster_type = user.broadcaster_type.clone();
+        self.profile_image_url = user.profile_image_url.clone();
+    }
 
-    let mut streams = get_streams(&agent, query, languages, tag_cache, &token);
+    /// The name to show for the user, the display name if it only differs by case from the login
+    pub fn name(&self) -> &str {
+        if self.display_name.eq_ignore_ascii_case(&self.user_name) {
+            &self.display_name
+        } else {
+            &self.user_name
+        }
+    }
 
-    // fix up the time
-    for (_, stream) in &mut streams {
-        let (seconds, started_at) = format_time(&stream.started_at);
-        stream.uptime = seconds;
-        stream.started_at = started_at.into();
+    /// The localized display name, if it is different from the login
+    pub fn localized_name(&self) -> Option<&str> {
+        (!self.display_name.is_empty() && !self.display_name.eq_ignore_ascii_case(&self.user_name))
+            .then_some(&*self.display_name)
     }
 
-    // then fetch usernames for each userid
-    for streams in streams.chunks_mut(100) {
-        let user_ids = streams.iter_mut().map(|(_, u)| &*u.user_id);
-        for (k, v) in get_usernames(&agent, user_ids, &token)? {
-            if let Some((_, stream)) = streams.iter_mut().find(|(_, s)| *s.user_id == k) {
-                stream.user_name = v.into();
+    /// Sets the uptime from when the stream started, and makes `started_at` readable
+    pub fn fix_uptime(&mut self) {
+        let (seconds, started_at) = format_time(&self.started_at);
+        self.uptime = seconds;
+        self.started_at = started_at.into();
+    }
+}
+
+// How many user lookups can happen at the same time
+const LOOKUP_WORKERS: usize = 4;
+
+// How many follower counts can be fetched at the same time
+const FOLLOWER_WORKERS: usize = 4;
+
+/// What to search for
+#[derive(Debug, Copy, Clone)]
+pub struct Search<'a> {
+    pub query: &'a [Query],
+    pub languages: &'a [String],
+    pub categories: &'a [String],
+    pub sort: Option<SortAction>,
+    pub limit: Limit,
+    pub filter: &'a Filter,
+    /// Fetch the follower count of each matched user
+    pub followers: bool,
+    /// Only search the live channels the logged in user follows
+    pub following: bool,
+    /// Also search for live channels in any category
+    pub everywhere: bool,
+    /// Let the terms match loosely when they don't match exactly
+    pub fuzzy: bool,
+}
+
+impl<'a> Search<'a> {
+    // Twitch returns streams by viewer count, descending. so if that is the order we want,
+    // later pages cannot have better matches than the ones we already have
+    fn can_stop_early(&self) -> bool {
+        match self.sort {
+            None => true,
+            Some(SortAction { column, direction }) => {
+                column == Column::Viewers && direction == Direction::Descending
             }
         }
     }
 
-    for (_, stream) in &mut streams {
-        for id in &*stream.tag_ids {
-            if let Some(tag) = tag_cache.cache.get(id) {
-                stream.user_tag_map.insert(id.clone(), tag.clone());
+    // whether enough streams have been matched, the counts are per query
+    fn is_satisfied(&self, counts: &HashMap<&String, usize>) -> bool {
+        // some filters are applied after fetching, so the counts could be too high
+        if !self.can_stop_early() || self.filter.is_deferred() {
+            return false;
+        }
+
+        let Limit {
+            total, per_query, ..
+        } = self.limit;
+
+        if let Some(total) = total {
+            if counts.values().sum::<usize>() >= total {
+                return true;
             }
         }
+
+        per_query.is_some_and(|per_query| {
+            self.query
+                .iter()
+                .all(|q| counts.get(&q.label).copied().unwrap_or_default() >= per_query)
+        })
     }
+}
 
-    Ok(streams)
+/// Limits on how many streams to fetch and show
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Limit {
+    /// The most streams to show overall
+    pub total: Option<usize>,
+    /// The most streams to show for each query
+    pub per_query: Option<usize>,
+    /// The most pages of streams to fetch
+    pub max_pages: Option<usize>,
 }
 
-pub fn sort_streams(streams: &mut Vec<Stream>, option: Option<SortAction>) {
-    use {Column::*, Direction::*};
+/// The streams that matched a query, and any problems encountered while fetching them
+#[derive(Debug, Default)]
+pub struct Fetched<'a> {
+    pub streams: Vec<(&'a String, Stream)>,
+    /// The matched streams that were hidden by the filter
+    pub excluded: Vec<Excluded>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Fetches streams matching the query
+///
+/// This returns an error if nothing could be fetched, failures after that are reported as [`Warning`]s
+pub fn fetch_streams<'a>(
+    search: Search<'a>,
+    client: &mut Client,
+    tag_cache: &TagCache,
+    category_cache: &mut CategoryCache,
+    follower_cache: &mut FollowerCache,
+) -> anyhow::Result<Fetched<'a>> {
+    client.retry_unauthorized(|client| {
+        try_fetch_streams(search, client, tag_cache, category_cache, follower_cache)
+    })
+}
+
+fn try_fetch_streams<'a>(
+    search: Search<'a>,
+    client: &Client,
+    tag_cache: &TagCache,
+    category_cache: &mut CategoryCache,
+    follower_cache: &mut FollowerCache,
+) -> anyhow::Result<Fetched<'a>> {
+    let mut warnings = vec![];
+
+    // the followed streams can't be filtered by category
+    let categories = if search.following {
+        vec![]
+    } else {
+        crate::resolve_categories(client, search.categories, category_cache)?
+    };
+
+    // the users for a page are looked up by the workers while the next page is being fetched
+    let (jobs_tx, jobs_rx) = mpsc::sync_channel::<Page<'a>>(LOOKUP_WORKERS);
+    let jobs_rx = Mutex::new(jobs_rx);
+
+    let (result, mut pages) = std::thread::scope(|scope| {
+        let (results_tx, results_rx) = mpsc::channel();
+
+        for _ in 0..LOOKUP_WORKERS {
+            let (jobs_rx, results_tx) = (&jobs_rx, results_tx.clone());
+            scope.spawn(move || {
+                // the lock is released before the lookup happens
+                while let Ok(mut page) = { jobs_rx.lock().unwrap().recv() } {
+                    let users = resolve_users(client, &mut page.streams).err();
+                    // the labels aren't part of the stream, so only look them up if they're filtered by
+                    let labels = (!search.filter.hide_labels.is_empty())
+                        .then(|| {
+                            let streams = page.streams.iter_mut().map(|(_, stream)| stream);
+                            resolve_labels(client, streams).err()
+                        })
+                        .flatten();
+                    if results_tx.send((page, users, labels)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(results_tx);
+
+        // this only fails if every worker has stopped, in which case there's nothing to do
+        let send = |page| {
+            let _ = jobs_tx.send(page);
+        };
+
+        let mut result = get_streams(client, search, &categories, tag_cache, &mut warnings, send);
+        if result.is_ok() && search.everywhere && !search.following {
+            result = search_everywhere(client, search, tag_cache, &mut warnings)
+                .map(|page| page.into_iter().for_each(send));
+        }
+        drop(jobs_tx);
 
+        (result, results_rx.iter().collect::<Vec<_>>())
+    });
+    result?;
+
+    pages.sort_unstable_by_key(|(page, ..)| page.number);
+
+    let mut streams = Vec::new();
+    for (page, users, labels) in pages {
+        match users {
+            Some(err) if is_unauthorized(&err) => return Err(err),
+            Some(err) => warnings.push(Warning::users(&err)),
+            None => {}
+        }
+        match labels {
+            Some(err) if is_unauthorized(&err) => return Err(err),
+            Some(err) => warnings.push(Warning::labels(&err)),
+            None => {}
+        }
+        streams.extend(page.streams);
+    }
+
+    // a stream can be found in more than one way, e.g. by its category and by the channel search
+    let mut seen = std::collections::HashSet::new();
+    streams.retain(|(_, stream)| seen.insert(stream.user_id.clone()));
+
+    if search.followers {
+        match resolve_followers(client, &mut streams, follower_cache) {
+            Err(err) if is_unauthorized(&err) => return Err(err),
+            Err(err) => warnings.push(Warning::followers(&err)),
+            Ok(()) => {}
+        }
+    }
+
+    let (mut streams, excluded) = exclude_streams(streams, search.filter);
+
+    for (_, stream) in &mut streams {
+        stream.fix_uptime();
+    }
+
+    Ok(Fetched {
+        streams,
+        excluded,
+        warnings,
+    })
+}
+
+/// Removes the duplicate streams, and then sorts them
+pub fn sort_streams(streams: &mut Vec<Stream>, option: Option<SortAction>) {
     // TODO figure out a way around this: https://github.com/twitchdev/issues/issues/18
     // sometimes the api hiccups -- this'll ensure we'll just get uniques
     streams.sort_unstable_by(|a, b| a.user_id.cmp(&b.user_id));
@@ -73,104 +312,137 @@ pub fn sort_streams(streams: &mut Vec<Stream>, option: Option<SortAction>) {
 
     streams.sort_unstable_by(|left, right| {
         option
-            .map(|sort| {
-                let SortAction { column, direction } = sort;
-                let ordering = match column {
-                    Viewers => left.viewer_count.cmp(&right.viewer_count),
-                    Uptime => left.uptime.cmp(&right.uptime),
-                    // invert this so its a->z not z->a
-                    Name => right.user_name.cmp(&left.user_name),
-                };
-
-                match direction {
-                    Ascending => ordering,
-                    Descending => ordering.reverse(),
-                }
-            })
+            .map(|sort| compare_streams(left, right, sort))
             .unwrap_or_else(|| left.viewer_count.cmp(&right.viewer_count))
     });
 }
 
-fn lookup_ids<'a>(
-    agent: &ureq::Agent,
-    token: &str,
-    ids: impl IntoIterator<Item = &'a str> + 'a,
-    memo: &mut TagCache,
+/// Sorts each group of streams, and then only keeps the best streams according to the limit
+///
+/// Without a sort, the streams with the most viewers are kept
+pub fn limit_streams(
+    groups: &mut HashMap<String, Vec<Stream>>,
+    option: Option<SortAction>,
+    limit: Limit,
 ) {
-    #[derive(serde::Deserialize)]
-    struct Tag {
-        tag_id: Box<str>,
-        #[serde(default)]
-        is_auto: bool,
-        localization_names: HashMap<Box<str>, Box<str>>,
+    let rank = option.unwrap_or(SortAction {
+        column: Column::Viewers,
+        direction: Direction::Descending,
+    });
+
+    for streams in groups.values_mut() {
+        sort_streams(streams, Some(rank));
+        if let Some(per_query) = limit.per_query {
+            streams.truncate(per_query);
+        }
+    }
+    groups.retain(|_, streams| !streams.is_empty());
+
+    if let Some(total) = limit.total {
+        let mut all = groups
+            .values()
+            .flatten()
+            .map(|s| (&*s.user_id, s))
+            .collect::<Vec<_>>();
+        all.sort_by(|(_, left), (_, right)| compare_streams(left, right, rank));
+
+        let keep = all
+            .into_iter()
+            .take(total)
+            .map(|(id, _)| Box::<str>::from(id))
+            .collect::<std::collections::HashSet<_>>();
+
+        for streams in groups.values_mut() {
+            streams.retain(|s| keep.contains(&s.user_id));
+        }
+        groups.retain(|_, streams| !streams.is_empty());
     }
 
-    type Tags = data::Resp<Tag>;
+    for streams in groups.values_mut() {
+        sort_streams(streams, option);
+    }
+}
 
-    let resp = match std::iter::repeat("tag_id")
-        .zip(ids)
-        .fold(
-            agent.get("https://api.twitch.tv/helix/tags/streams"),
-            |req, (k, v)| req.query(k, v),
-        )
-        .set("client-id", WHAT_STREAM_CLIENT_ID)
-        .set("authorization", token)
-        .call()
-    {
-        Ok(resp) => resp.into_reader(),
-        Err(..) => return, // TODO report this
-    };
+fn compare_streams(left: &Stream, right: &Stream, sort: SortAction) -> std::cmp::Ordering {
+    use {Column::*, Direction::*};
 
-    let tags = match serde_json::from_reader::<_, Tags>(resp) {
-        Ok(tags) => tags,
-        Err(..) => return, // TODO report this
+    let SortAction { column, direction } = sort;
+    let ordering = match column {
+        Viewers => left.viewer_count.cmp(&right.viewer_count),
+        Uptime => left.uptime.cmp(&right.uptime),
+        Followers => left.followers.cmp(&right.followers),
+        // invert this so its a->z not z->a
+        Name => right.user_name.cmp(&left.user_name),
     };
 
-    for data in tags.data.into_iter().filter(|s| !s.is_auto) {
-        if let Some(name) = { data.localization_names }.remove("en-us") {
-            memo.cache.insert(data.tag_id, name);
-        }
+    match direction {
+        Ascending => ordering,
+        Descending => ordering.reverse(),
     }
 }
 
-mod data {
-    #[derive(serde::Deserialize)]
-    pub struct Resp<T> {
-        pub data: Vec<T>,
-        pub pagination: Pagination,
-    }
+fn get_streams<'a>(
+    client: &Client,
+    search: Search<'a>,
+    categories: &[String],
+    tag_cache: &TagCache,
+    warnings: &mut Vec<Warning>,
+    mut on_page: impl FnMut(Page<'a>),
+) -> anyhow::Result<()> {
+    type Streams = Data<Vec<Stream>>;
 
-    #[derive(Default, serde::Deserialize)]
-    pub struct Pagination {
-        #[serde(default)]
-        pub cursor: String,
-    }
-}
+    let query = search.query;
 
-fn get_streams<'a>(
-    agent: &ureq::Agent,
-    query: &'a [String],
-    languages: &[String],
-    tags: &mut TagCache,
-    token: &str,
-) -> Vec<(&'a String, Stream)> {
-    type Streams = data::Resp<Stream>;
+    let languages = normalize_languages(search.languages);
+
+    // let the api filter by language, unless it rejects one of them
+    let mut server_side_languages = !languages.is_empty() && !search.following;
+
+    let mut counts = HashMap::<&String, usize>::new();
 
-    let mut streams = Vec::new();
     let mut cursor = String::new();
-    while let Ok(resp) = agent
-        .get("https://api.twitch.tv/helix/streams")
-        .query("game_id", SCIENCE_AND_TECH_CATEGORY)
-        .query("game_id", SOFTWARE_AND_GAME_DEV_CATEGORY)
-        .query("first", "100")
-        .query("after", &cursor)
-        .set("client-id", WHAT_STREAM_CLIENT_ID)
-        .set("authorization", token)
-        .call()
-        .map(ureq::Response::into_reader)
-    {
-        let mut resp = match serde_json::from_reader::<_, Streams>(resp) {
-            Err(..) => break, // TODO report this
+    let mut page = 0;
+    loop {
+        if search.is_satisfied(&counts) {
+            break;
+        }
+
+        if matches!(search.limit.max_pages, Some(max) if page >= max) {
+            break;
+        }
+
+        page += 1;
+
+        let result = if search.following {
+            let user_id = client.user_access().map_or("", |user| &*user.user_id);
+            let params = [("user_id", user_id), ("first", "100"), ("after", &*cursor)];
+            client.get_json_as_user::<Streams>("streams/followed", &params)
+        } else {
+            let params = std::iter::repeat("game_id")
+                .zip(categories.iter().map(|s| &**s))
+                .chain(
+                    std::iter::repeat("language")
+                        .zip(languages.iter().map(|s| &**s))
+                        .filter(|_| server_side_languages),
+                )
+                .chain([("first", "100"), ("after", &*cursor)])
+                .collect::<Vec<_>>();
+            client.get_json::<Streams>("streams", &params)
+        };
+
+        let mut resp = match result {
+            Err(err) if is_unauthorized(&err) => return Err(err),
+            Err(err) if page == 1 && server_side_languages && is_bad_request(&err) => {
+                // fall back to filtering the languages ourselves
+                server_side_languages = false;
+                page = 0;
+                continue;
+            }
+            Err(err) if page == 1 => return Err(err.context("cannot fetch streams")),
+            Err(err) => {
+                warnings.push(Warning::page(page, &err));
+                break;
+            }
             Ok(resp) if resp.data.is_empty() => break,
             Ok(resp) if resp.pagination.cursor == cursor => break,
             Ok(resp) => resp,
@@ -178,87 +450,258 @@ fn get_streams<'a>(
 
         cursor = resp.pagination.cursor;
         let mut temp = std::mem::take(&mut resp.data);
-        if !languages.is_empty() {
-            temp.retain(|stream| {
-                languages
-                    .iter()
-                    .any(|lang| stream.language.eq_ignore_ascii_case(lang))
+        temp.retain(|stream| matches_language(&languages, stream));
+
+        for stream in &mut temp {
+            resolve_legacy_tags(stream, tag_cache);
+        }
+
+        let streams = temp
+            .into_iter()
+            .filter_map(|stream| match_query(query, stream, search.fuzzy))
+            .collect::<Vec<_>>();
+
+        // streams that will be hidden don't count towards the limits
+        for (q, _) in streams
+            .iter()
+            .filter(|(_, stream)| search.filter.matches(stream))
+        {
+            *counts.entry(q).or_default() += 1;
+        }
+
+        if !streams.is_empty() {
+            on_page(Page {
+                number: page,
+                streams,
             });
         }
+    }
+
+    Ok(())
+}
 
-        let unknown_ids: HashSet<&str> = temp
+// searches for live channels matching the query in any category, their streams are put in a page after the others
+fn search_everywhere<'a>(
+    client: &Client,
+    search: Search<'a>,
+    tag_cache: &TagCache,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Option<Page<'a>>> {
+    type Streams = Data<Vec<Stream>>;
+
+    let mut terms = search
+        .query
+        .iter()
+        .flat_map(Query::terms)
+        .collect::<Vec<_>>();
+    terms.sort_unstable();
+    terms.dedup();
+
+    let mut ids = vec![];
+    for term in &terms {
+        match crate::search_channels(client, term, true) {
+            Err(err) if is_unauthorized(&err) => return Err(err),
+            Err(err) => warnings.push(Warning::search(term, &err)),
+            Ok(channels) => ids.extend(
+                channels
+                    .into_iter()
+                    .filter(|channel| channel.is_live)
+                    .map(|channel| channel.id),
+            ),
+        }
+    }
+    ids.sort_unstable();
+    ids.dedup();
+
+    // the search doesn't have the viewer counts, so get the streams for the channels
+    let mut temp = vec![];
+    for ids in ids.chunks(100) {
+        let params = std::iter::repeat("user_id")
+            .zip(ids.iter().map(|s| &**s))
+            .chain([("first", "100")])
+            .collect::<Vec<_>>();
+
+        match client.get_json::<Streams>("streams", &params) {
+            Err(err) if is_unauthorized(&err) => return Err(err),
+            Err(err) => warnings.push(Warning::search(&terms.join(" "), &err)),
+            Ok(resp) => temp.extend(resp.data),
+        }
+    }
+
+    let languages = normalize_languages(search.languages);
+    temp.retain(|stream| matches_language(&languages, stream));
+
+    for stream in &mut temp {
+        resolve_legacy_tags(stream, tag_cache);
+    }
+
+    let streams = temp
+        .into_iter()
+        .filter_map(|stream| match_query(search.query, stream, search.fuzzy))
+        .collect::<Vec<_>>();
+
+    Ok((!streams.is_empty()).then_some(Page {
+        number: usize::MAX,
+        streams,
+    }))
+}
+
+fn normalize_languages(languages: &[String]) -> Vec<String> {
+    languages
+        .iter()
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn matches_language(languages: &[String], stream: &Stream) -> bool {
+    languages.is_empty()
+        || languages
             .iter()
-            .flat_map(|s| &*s.tag_ids)
-            .filter_map(|s| (!tags.cache.contains_key(&**s)).then(|| &**s))
-            .collect();
-
-        lookup_ids(agent, token, unknown_ids, tags);
-
-        'stream: for stream in temp {
-            for id in &*stream.tag_ids {
-                if let Some(tag) = tags.cache.get(id) {
-                    for q in query {
-                        if q.eq_ignore_ascii_case(tag) {
-                            streams.push((q, stream));
-                            continue 'stream;
-                        }
-                    }
-                }
-            }
+            .any(|lang| stream.language.eq_ignore_ascii_case(lang))
+}
 
-            for part in stream
-                .title
-                .split(' ')
-                .map(trim_word_boundaries)
-                .filter(|s| !s.is_empty())
-            {
-                for q in query {
-                    if q.eq_ignore_ascii_case(part) {
-                        streams.push((q, stream));
-                        continue 'stream;
-                    }
-                }
-            }
+// finds the query that matched the stream best, exact matches win and then the tags are checked before the title
+fn match_query(query: &[Query], mut stream: Stream, fuzzy: bool) -> Option<(&String, Stream)> {
+    let text = Text::new(&stream, fuzzy);
+    let mut best: Option<(Hit, &String)> = None;
+    for q in query {
+        match q.find(&text) {
+            // an earlier query wins a tie
+            Some(hit) if best.is_none_or(|(best, _)| hit < best) => best = Some((hit, &q.label)),
+            _ => {}
         }
     }
 
-    streams
+    let (hit, label) = best?;
+    stream.matched = Some(hit.kind);
+    Some((label, stream))
+}
+
+// streams from before Twitch's freeform tags only have `tag_ids`, so use the names we cached for those
+fn resolve_legacy_tags(stream: &mut Stream, tag_cache: &TagCache) {
+    if stream.tag_ids.is_empty() {
+        return;
+    }
+
+    let mut tags = stream.tags.to_vec();
+    for tag in stream
+        .tag_ids
+        .iter()
+        .filter_map(|id| tag_cache.cache.get(id))
+    {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    stream.tags = tags.into();
 }
 
-fn get_usernames<'b: 'a, 'a, I>(
-    agent: &ureq::Agent,
-    ids: I,
-    token: &str,
-) -> anyhow::Result<HashMap<String, String>>
+pub(crate) fn nullable<'de, D, T>(de: D) -> Result<T, D::Error>
 where
-    I: Iterator<Item = &'b str> + 'a,
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de> + Default,
 {
-    #[derive(serde::Deserialize)]
-    struct Resp<T> {
-        data: Vec<T>,
+    <Option<T> as serde::Deserialize>::deserialize(de).map(Option::unwrap_or_default)
+}
+
+// a page of matched streams
+struct Page<'a> {
+    number: usize,
+    streams: Vec<(&'a String, Stream)>,
+}
+
+// fills in the user profile of each stream
+fn resolve_users(client: &Client, streams: &mut [(&String, Stream)]) -> anyhow::Result<()> {
+    for streams in streams.chunks_mut(100) {
+        let user_ids = streams.iter().map(|(_, u)| &*u.user_id);
+        let users = crate::user::get_users(client, "id", user_ids)?;
+        for (_, stream) in streams {
+            if let Some(user) = users.get(&stream.user_id) {
+                stream.set_user(user);
+            }
+        }
     }
+    Ok(())
+}
 
-    #[derive(serde::Deserialize)]
-    struct User {
-        id: String,
-        login: String,
+// fills in the content classification labels of each stream
+pub(crate) fn resolve_labels<'a>(
+    client: &Client,
+    streams: impl IntoIterator<Item = &'a mut Stream>,
+) -> anyhow::Result<()> {
+    let mut streams = streams.into_iter().collect::<Vec<_>>();
+    for streams in streams.chunks_mut(100) {
+        let user_ids = streams.iter().map(|u| &*u.user_id);
+        let channels = crate::get_channels(client, user_ids)?;
+        for stream in streams {
+            if let Some(channel) = channels.get(&stream.user_id) {
+                stream.content_classification_labels =
+                    channel.content_classification_labels.clone();
+            }
+        }
     }
+    Ok(())
+}
 
-    std::iter::repeat("id")
-        .zip(ids)
-        .fold(
-            agent.get("https://api.twitch.tv/helix/users"),
-            |req, (k, v)| req.query(k, v),
-        )
-        .set("client-id", WHAT_STREAM_CLIENT_ID)
-        .set("authorization", token)
-        .call()?
-        .into_json::<Resp<User>>()?
-        .data
+// separates the streams hidden by the filter, keeping why they were hidden
+fn exclude_streams<'a>(
+    streams: Vec<(&'a String, Stream)>,
+    filter: &Filter,
+) -> (Vec<(&'a String, Stream)>, Vec<Excluded>) {
+    let mut excluded = vec![];
+    let streams = streams
         .into_iter()
-        .map(|u| (u.id, u.login))
-        .map(Ok)
-        .collect()
+        .filter_map(|(query, stream)| {
+            let reasons = filter.reasons(&stream);
+            if reasons.is_empty() {
+                return Some((query, stream));
+            }
+            excluded.push(Excluded {
+                query: query.clone(),
+                user_name: stream.user_name,
+                user_id: stream.user_id,
+                reasons,
+            });
+            None
+        })
+        .collect();
+    (streams, excluded)
+}
+
+// fills in the follower count of each stream, the counts that are missing from the cache are fetched
+fn resolve_followers(
+    client: &Client,
+    streams: &mut [(&String, Stream)],
+    cache: &mut FollowerCache,
+) -> anyhow::Result<()> {
+    let mut missing = streams
+        .iter()
+        .map(|(_, stream)| stream.user_id.clone())
+        .filter(|id| cache.get(id).is_none())
+        .collect::<Vec<_>>();
+    missing.sort_unstable();
+    missing.dedup();
+
+    let results = map_shared(&missing, FOLLOWER_WORKERS, |id| {
+        crate::get_followers(client, id)
+    });
+
+    let mut error = None;
+    for (id, result) in results {
+        match result {
+            Ok(total) => cache.insert(id, total),
+            Err(err) => {
+                error.get_or_insert(err);
+            }
+        }
+    }
+
+    for (_, stream) in streams {
+        stream.followers = cache.get(&stream.user_id);
+    }
+
+    error.map_or(Ok(()), Err)
 }
 
 fn format_time(started_at: &str) -> (i64, String) {
@@ -290,11 +733,3 @@ fn format_time(started_at: &str) -> (i64, String) {
 
     (seconds, started)
 }
-
-fn trim_word_boundaries(s: &str) -> &str {
-    const HEAD: &str = "([{";
-    const TAIL: &str = ",.!?-:}])";
-    s.trim_start_matches(|c| HEAD.contains(c))
-        .trim_end_matches(|c| TAIL.contains(c))
-        .trim()
-}
@@ -0,0 +1,53 @@
+// What the app and user access tokens have in common
+
+use crate::http::{Endpoints, Method, Request, Transport};
+
+// Tokens that expire within this many seconds are considered expired
+const EXPIRY_MARGIN: i64 = 5 * 60;
+
+/// Whether a token that expires at this unix timestamp should be replaced
+pub(crate) fn is_expired(expires_at: i64) -> bool {
+    expires_at - chrono::Utc::now().timestamp() <= EXPIRY_MARGIN
+}
+
+/// What Twitch knows about a valid token
+#[derive(serde::Deserialize)]
+pub(crate) struct Validation {
+    pub(crate) client_id: String,
+    /// The login of the user, app access tokens don't have one
+    #[serde(default)]
+    pub(crate) login: String,
+    #[serde(default)]
+    pub(crate) user_id: String,
+    pub(crate) expires_in: u64,
+}
+
+impl Validation {
+    /// When the token expires, as a unix timestamp
+    pub(crate) fn expires_at(&self) -> i64 {
+        chrono::Utc::now().timestamp() + self.expires_in as i64
+    }
+}
+
+/// Checks that the token is still valid and was issued for the client id
+///
+/// This is `None` if it isn't, or if it could not be checked
+// https://dev.twitch.tv/docs/authentication/validate-tokens
+pub(crate) fn validate(
+    transport: &dyn Transport,
+    endpoints: &Endpoints,
+    access_token: &str,
+    client_id: &str,
+) -> Option<Validation> {
+    transport
+        .send(Request {
+            method: Method::Get,
+            url: &endpoints.auth("validate"),
+            query: &[],
+            headers: &[("authorization", &format!("OAuth {}", access_token))],
+        })
+        .ok()
+        .filter(|resp| resp.is_success())
+        .and_then(|resp| resp.json::<Validation>().ok())
+        .filter(|validation| validation.client_id == client_id)
+}
@@ -0,0 +1,140 @@
+// Splits stream titles into the words that the query is matched against
+
+// These can be part of a word, e.g. `C++`, `C#`, `.NET`, `node.js`, `Rust's` and `game-dev`
+const JOINERS: &[char] = &['+', '#', '.', '\'', '’', '-', '_'];
+
+/// Splits a title into words
+///
+/// The words are separated by whitespace, `/`, `|`, a `+` between two words (e.g. `rust+bevy`),
+/// and any other punctuation or symbols, like brackets and emoji. Hashtags are words without the `#`,
+/// and language names like `C++`, `C#`, `F#`, `.NET` and `node.js` are kept intact
+pub(crate) fn tokenize(title: &str) -> Vec<&str> {
+    let chars = title.char_indices().collect::<Vec<_>>();
+
+    let mut words = vec![];
+    let mut start = 0;
+    for (n, &(i, ch)) in chars.iter().enumerate() {
+        let prev = n.checked_sub(1).map(|n| chars[n].1);
+        let next = chars.get(n + 1).map(|&(_, ch)| ch);
+        if is_separator(prev, ch, next) {
+            words.extend(trim(&title[start..i]));
+            start = i + ch.len_utf8();
+        }
+    }
+    words.extend(trim(&title[start..]));
+    words
+}
+
+fn is_separator(prev: Option<char>, ch: char, next: Option<char>) -> bool {
+    match ch {
+        '/' | '|' => true,
+        // `rust+bevy`, but not `C++` or `C++20`
+        '+' => prev.is_some_and(char::is_alphanumeric) && next.is_some_and(char::is_alphanumeric),
+        ch => !ch.is_alphanumeric() && !JOINERS.contains(&ch),
+    }
+}
+
+// removes the joiners from the ends of a word, unless they're part of it
+fn trim(word: &str) -> Option<&str> {
+    // hashtags, and things like `-rust` or `'rust'`
+    let word = word.trim_start_matches(['#', '+', '-', '_', '\'', '’']);
+
+    // `.NET`, but not `...and`
+    let rest = word.trim_start_matches('.');
+    let word = match word.len() - rest.len() {
+        1 if rest.starts_with(char::is_alphabetic) => word,
+        _ => rest,
+    };
+
+    // `C++` and `C#`, but not `rust.` or `rust'`
+    let trimmed = word.trim_end_matches(JOINERS);
+    let suffix = &word[trimmed.len()..];
+    let keep = if suffix.starts_with("++") {
+        2
+    } else if suffix.starts_with('#') && trimmed.ends_with(char::is_alphabetic) {
+        1
+    } else {
+        0
+    };
+
+    Some(&word[..trimmed.len() + keep]).filter(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles() {
+        let titles: &[(&str, &[&str])] = &[
+            ("learning rust", &["learning", "rust"]),
+            (
+                "Writing a #rust game engine | bevy",
+                &["Writing", "a", "rust", "game", "engine", "bevy"],
+            ),
+            (
+                "rust/wasm game jam! come hang out",
+                &["rust", "wasm", "game", "jam", "come", "hang", "out"],
+            ),
+            (
+                "Rust|Bevy|WGPU - day 12",
+                &["Rust", "Bevy", "WGPU", "day", "12"],
+            ),
+            (
+                "making a game in Rust+Bevy",
+                &["making", "a", "game", "in", "Rust", "Bevy"],
+            ),
+            (
+                "Modern C++ (C++20) and some C",
+                &["Modern", "C++", "C++20", "and", "some", "C"],
+            ),
+            (
+                "Unity (C#) vs Godot (GDScript)",
+                &["Unity", "C#", "vs", "Godot", "GDScript"],
+            ),
+            (
+                "F# and C#, then .NET 8",
+                &["F#", "and", "C#", "then", ".NET", "8"],
+            ),
+            (
+                "building an API with node.js.",
+                &["building", "an", "API", "with", "node.js"],
+            ),
+            (
+                "[EN] Advent of Code 2024 - Day 3 🎄🦀",
+                &["EN", "Advent", "of", "Code", "2024", "Day", "3"],
+            ),
+            ("🦀rust🦀 all day", &["rust", "all", "day"]),
+            (
+                "#100DaysOfCode #gamedev #indiedev",
+                &["100DaysOfCode", "gamedev", "indiedev"],
+            ),
+            (
+                "Rust's borrow checker: a love story",
+                &["Rust's", "borrow", "checker", "a", "love", "story"],
+            ),
+            (
+                "game-dev stream... lets go!!",
+                &["game-dev", "stream", "lets", "go"],
+            ),
+            (
+                "C/C++ and Zig | !discord !commands",
+                &["C", "C++", "and", "Zig", "discord", "commands"],
+            ),
+            (
+                "TypeScript + React + Vue.js",
+                &["TypeScript", "React", "Vue.js"],
+            ),
+            (
+                "\"quoted\" 'words' and {braces}",
+                &["quoted", "words", "and", "braces"],
+            ),
+            ("...and we're back", &["and", "we're", "back"]),
+            ("   ", &[]),
+        ];
+
+        for (title, expected) in titles {
+            assert_eq!(tokenize(title), *expected, "{}", title);
+        }
+    }
+}
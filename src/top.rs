@@ -0,0 +1,126 @@
+use crate::{
+    helix::{is_unauthorized, Data},
+    pool::map_shared,
+    Category, Client, Warning,
+};
+
+// How many categories can be counted at the same time
+const COUNT_WORKERS: usize = 4;
+
+/// How busy a category is
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CategoryStats {
+    pub id: Box<str>,
+    pub name: Box<str>,
+    /// How many live streams there are
+    pub streams: usize,
+    /// The total number of viewers of those streams
+    pub viewers: u64,
+    /// Whether every page of streams was counted, otherwise the counts are a lower bound
+    pub complete: bool,
+}
+
+/// The categories that were counted, and any problems encountered while counting them
+#[derive(Debug, Default)]
+pub struct Top {
+    pub categories: Vec<CategoryStats>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Gets the categories with the most viewers, at most 100
+// https://dev.twitch.tv/docs/api/reference/#get-top-games
+pub fn get_top_categories(client: &Client, count: usize) -> anyhow::Result<Vec<Category>> {
+    let first = count.clamp(1, 100).to_string();
+    client.get_data("games/top", &[("first", &first)])
+}
+
+/// Counts the live streams and viewers of each category, sorted by the viewers
+///
+/// Only `max_pages` pages of streams are counted for each category, if it is set
+pub fn count_categories(
+    client: &mut Client,
+    categories: &[Category],
+    languages: &[String],
+    max_pages: Option<usize>,
+) -> anyhow::Result<Top> {
+    client
+        .retry_unauthorized(|client| try_count_categories(client, categories, languages, max_pages))
+}
+
+fn try_count_categories(
+    client: &Client,
+    categories: &[Category],
+    languages: &[String],
+    max_pages: Option<usize>,
+) -> anyhow::Result<Top> {
+    let results = map_shared(categories, COUNT_WORKERS, |category| {
+        count_category(client, category, languages, max_pages)
+    });
+
+    let mut top = Top::default();
+    for (category, result) in results {
+        match result {
+            Ok(stats) => top.categories.push(stats),
+            Err(err) if is_unauthorized(&err) => return Err(err),
+            Err(err) => top.warnings.push(Warning::count(&category.name, &err)),
+        }
+    }
+
+    top.categories
+        .sort_by_key(|stats| std::cmp::Reverse(stats.viewers));
+    Ok(top)
+}
+
+fn count_category(
+    client: &Client,
+    category: &Category,
+    languages: &[String],
+    max_pages: Option<usize>,
+) -> anyhow::Result<CategoryStats> {
+    #[derive(serde::Deserialize)]
+    struct Stream {
+        viewer_count: u64,
+    }
+
+    let mut stats = CategoryStats {
+        id: category.id.clone(),
+        name: category.name.clone(),
+        streams: 0,
+        viewers: 0,
+        complete: true,
+    };
+
+    let mut cursor = String::new();
+    let mut page = 0;
+    loop {
+        page += 1;
+
+        let params = std::iter::repeat("language")
+            .zip(languages.iter().map(|s| &**s))
+            .chain([
+                ("game_id", &*category.id),
+                ("first", "100"),
+                ("after", &*cursor),
+            ])
+            .collect::<Vec<_>>();
+
+        let resp = client.get_json::<Data<Vec<Stream>>>("streams", &params)?;
+        stats.streams += resp.data.len();
+        stats.viewers += resp.data.iter().map(|s| s.viewer_count).sum::<u64>();
+
+        if resp.data.is_empty()
+            || resp.pagination.cursor.is_empty()
+            || resp.pagination.cursor == cursor
+        {
+            break;
+        }
+
+        if matches!(max_pages, Some(max) if page >= max) {
+            stats.complete = false;
+            break;
+        }
+        cursor = resp.pagination.cursor;
+    }
+
+    Ok(stats)
+}
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use crate::Client;
+
+/// A Twitch user, from `helix/users`
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct User {
+    pub id: Box<str>,
+    pub login: Box<str>,
+    pub display_name: Box<str>,
+    #[serde(default)]
+    pub description: Box<str>,
+    /// Either `partner`, `affiliate` or empty
+    #[serde(default)]
+    pub broadcaster_type: Box<str>,
+    #[serde(default)]
+    pub profile_image_url: Box<str>,
+}
+
+/// Looks up users by `id` or by `login`, at most 100 at a time
+///
+/// The returned map is keyed by the user id
+pub fn get_users<'a>(
+    client: &Client,
+    key: &str,
+    values: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Result<HashMap<Box<str>, User>> {
+    let query = std::iter::repeat(key).zip(values).collect::<Vec<_>>();
+    Ok(client
+        .get_data::<User>("users", &query)?
+        .into_iter()
+        .map(|user| (user.id.clone(), user))
+        .collect())
+}
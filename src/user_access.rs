@@ -0,0 +1,261 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context as _;
+
+use crate::{
+    config::write_private_json,
+    http::{Endpoints, Method, Request, Transport},
+    token, Credentials,
+};
+
+// The scopes the user token is requested with
+const SCOPES: &str = "user:read:follows";
+
+/// A user access token, from the device code grant
+///
+/// This is stored (with 0600 permissions) after `what_stream login`, and is needed for `--following`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct UserAccess {
+    pub access_token: String,
+    pub refresh_token: String,
+
+    /// When the token expires, as a unix timestamp
+    pub expires_at: i64,
+
+    /// The client id this token was issued for
+    pub client_id: String,
+
+    /// The id of the user this token belongs to
+    pub user_id: String,
+    pub login: String,
+
+    #[serde(skip)]
+    client_secret: String,
+}
+
+/// What the user has to do to finish logging in
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    /// The url the user has to visit, the code is already filled in
+    pub verification_uri: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+// the parts of a token response that we use
+#[derive(serde::Deserialize)]
+struct Token {
+    access_token: String,
+    refresh_token: String,
+    expires_in: u64,
+}
+
+impl UserAccess {
+    pub fn get_token_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|f| f.join("museun").join("what_stream").join("user_token.json"))
+    }
+
+    /// Logs in with the device code grant
+    ///
+    /// `on_code` is called with what the user has to do, this then waits until they've done it
+    // https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#device-code-grant-flow
+    pub fn login(
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+        credentials: &Credentials,
+        on_code: impl FnOnce(&DeviceCode),
+    ) -> anyhow::Result<Self> {
+        let code: DeviceCode = transport
+            .send(Request {
+                method: Method::Post,
+                url: &endpoints.auth("device"),
+                query: &[("client_id", &credentials.client_id), ("scopes", SCOPES)],
+                headers: &[],
+            })?
+            .error_for_status()?
+            .json()?;
+
+        on_code(&code);
+
+        let deadline = chrono::Utc::now().timestamp() + code.expires_in as i64;
+        let mut interval = code.interval.max(1);
+
+        let token = loop {
+            std::thread::sleep(Duration::from_secs(interval));
+            anyhow::ensure!(
+                chrono::Utc::now().timestamp() < deadline,
+                "the login code expired, try logging in again"
+            );
+
+            let err = match transport
+                .send(Request {
+                    method: Method::Post,
+                    url: &endpoints.auth("token"),
+                    query: &[
+                        ("client_id", &credentials.client_id),
+                        ("scopes", SCOPES),
+                        ("device_code", &code.device_code),
+                        ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                    ],
+                    headers: &[],
+                })?
+                .error_for_status()
+            {
+                Ok(resp) => break resp.json::<Token>()?,
+                Err(err) => err,
+            };
+
+            match &*error_message(&err.body) {
+                "authorization_pending" => continue,
+                "slow_down" => interval += 5,
+                _ => return Err(anyhow::Error::new(err).context("cannot log in")),
+            }
+        };
+
+        let mut this = Self {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_at: chrono::Utc::now().timestamp() + token.expires_in as i64,
+            client_id: credentials.client_id.clone(),
+            user_id: String::new(),
+            login: String::new(),
+            client_secret: credentials.client_secret.clone(),
+        };
+
+        anyhow::ensure!(
+            this.validate(transport, endpoints),
+            "Twitch did not accept the new token"
+        );
+
+        this.sync()?;
+        Ok(this)
+    }
+
+    /// Gets the stored user token, refreshing it if it has expired
+    pub fn get(
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+        credentials: &Credentials,
+    ) -> anyhow::Result<Self> {
+        let mut this =
+            Self::load().with_context(|| "you are not logged in, run `what_stream login` first")?;
+
+        anyhow::ensure!(
+            this.client_id == credentials.client_id,
+            "you logged in with a different client id, run `what_stream login` again"
+        );
+        this.client_secret = credentials.client_secret.clone();
+
+        if this.is_expired() || !this.validate(transport, endpoints) {
+            this.renew(transport, endpoints)?;
+        }
+        Ok(this)
+    }
+
+    /// Uses the refresh token to get a new token, replacing this one
+    pub fn renew(
+        &mut self,
+        transport: &dyn Transport,
+        endpoints: &Endpoints,
+    ) -> anyhow::Result<()> {
+        let mut query = vec![
+            ("client_id", &*self.client_id),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &*self.refresh_token),
+        ];
+        // public clients don't have a secret
+        if !self.client_secret.is_empty() {
+            query.push(("client_secret", &self.client_secret));
+        }
+
+        let token: Token = transport
+            .send(Request {
+                method: Method::Post,
+                url: &endpoints.auth("token"),
+                query: &query,
+                headers: &[],
+            })?
+            .error_for_status()
+            .with_context(|| "cannot refresh the login, run `what_stream login` again")?
+            .json()?;
+
+        self.access_token = token.access_token;
+        self.refresh_token = token.refresh_token;
+        self.expires_at = chrono::Utc::now().timestamp() + token.expires_in as i64;
+
+        // refresh tokens can only be used once, so the new one has to be kept
+        self.sync()
+    }
+
+    /// Revokes the token and removes it
+    ///
+    /// This returns `false` if there wasn't a stored token
+    pub fn logout(transport: &dyn Transport, endpoints: &Endpoints) -> anyhow::Result<bool> {
+        let this = match Self::load() {
+            Some(this) => this,
+            None => return Ok(false),
+        };
+
+        let resp = transport.send(Request {
+            method: Method::Post,
+            url: &endpoints.auth("revoke"),
+            query: &[
+                ("client_id", &this.client_id),
+                ("token", &this.access_token),
+            ],
+            headers: &[],
+        })?;
+
+        // an invalid token has already been revoked
+        if resp.status != 400 {
+            resp.error_for_status()
+                .with_context(|| "cannot revoke the token")?;
+        }
+
+        let path = Self::get_token_path().with_context(|| "cannot get the token path")?;
+        std::fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        token::is_expired(self.expires_at)
+    }
+
+    fn validate(&mut self, transport: &dyn Transport, endpoints: &Endpoints) -> bool {
+        let validation =
+            match token::validate(transport, endpoints, &self.access_token, &self.client_id) {
+                Some(validation) if !validation.user_id.is_empty() => validation,
+                _ => return false,
+            };
+
+        self.expires_at = validation.expires_at();
+        self.login = validation.login;
+        self.user_id = validation.user_id;
+        !self.is_expired()
+    }
+
+    fn load() -> Option<Self> {
+        Self::get_token_path()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|s| serde_json::from_slice(&s).ok())
+    }
+
+    fn sync(&self) -> anyhow::Result<()> {
+        let path = Self::get_token_path().with_context(|| "cannot get the token path")?;
+        // the tokens are secrets
+        write_private_json(&path, self)
+    }
+}
+
+// the oauth endpoints report errors as `{ "status": 400, "message": "authorization_pending" }`
+fn error_message(body: &str) -> String {
+    #[derive(serde::Deserialize)]
+    struct Error {
+        message: String,
+    }
+    serde_json::from_str::<Error>(body)
+        .map(|err| err.message)
+        .unwrap_or_default()
+}
@@ -0,0 +1,29 @@
+use crate::Client;
+
+/// A video of a channel, from `helix/videos`
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Video {
+    pub id: Box<str>,
+    pub title: Box<str>,
+    pub url: Box<str>,
+    /// When the video was created, as a RFC3339 UTC timestamp
+    pub created_at: Box<str>,
+    /// How long the video is, e.g. `3h8m33s`
+    #[serde(default)]
+    pub duration: Box<str>,
+}
+
+/// Gets the most recent past broadcast of a user, if they have one
+// https://dev.twitch.tv/docs/api/reference/#get-videos
+pub fn get_last_broadcast(client: &Client, user_id: &str) -> anyhow::Result<Option<Video>> {
+    let query = [
+        ("user_id", user_id),
+        ("type", "archive"),
+        ("sort", "time"),
+        ("first", "1"),
+    ];
+    Ok(client
+        .get_data::<Video>("videos", &query)?
+        .into_iter()
+        .next())
+}
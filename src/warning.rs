@@ -0,0 +1,125 @@
+/// A non-fatal problem that happened while fetching, the results may be incomplete
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    /// A page of streams could not be fetched, so the results were truncated
+    Page { page: usize, error: String },
+    /// The user names could not be looked up
+    Users { error: String },
+    /// The content classification labels could not be looked up, so they weren't filtered by
+    Labels { error: String },
+    /// Some of the follower counts could not be fetched
+    Followers { error: String },
+    /// The channels on the watchlist could not be fetched
+    Watchlist { error: String },
+    /// The channel search for a query failed, so only the categories were searched
+    Search { query: Box<str>, error: String },
+    /// The streams in a category could not be counted
+    Count { category: Box<str>, error: String },
+    /// The schedule of a channel could not be fetched
+    Schedule { login: Box<str>, error: String },
+    /// Some of the logins don't belong to any user
+    UnknownLogins { logins: Vec<Box<str>> },
+    /// A cache could not be written to disk
+    Cache { name: &'static str, error: String },
+}
+
+impl Warning {
+    pub(crate) fn page(page: usize, err: &anyhow::Error) -> Self {
+        Self::Page {
+            page,
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub(crate) fn users(err: &anyhow::Error) -> Self {
+        Self::Users {
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub(crate) fn labels(err: &anyhow::Error) -> Self {
+        Self::Labels {
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub fn followers(err: &anyhow::Error) -> Self {
+        Self::Followers {
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub fn watchlist(err: &anyhow::Error) -> Self {
+        Self::Watchlist {
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub(crate) fn search(query: &str, err: &anyhow::Error) -> Self {
+        Self::Search {
+            query: query.into(),
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub(crate) fn count(category: &str, err: &anyhow::Error) -> Self {
+        Self::Count {
+            category: category.into(),
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub(crate) fn schedule(login: &str, err: &anyhow::Error) -> Self {
+        Self::Schedule {
+            login: login.into(),
+            error: format!("{:#}", err),
+        }
+    }
+
+    pub fn unknown_logins(logins: &[Box<str>]) -> Self {
+        Self::UnknownLogins {
+            logins: logins.to_vec(),
+        }
+    }
+
+    pub fn cache(name: &'static str, err: &anyhow::Error) -> Self {
+        Self::Cache {
+            name,
+            error: format!("{:#}", err),
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Page { page, error } => write!(
+                f,
+                "cannot fetch page {} of streams, the results are incomplete: {}",
+                page, error
+            ),
+            Self::Users { error } => write!(f, "cannot look up user names: {}", error),
+            Self::Labels { error } => write!(
+                f,
+                "cannot look up content classification labels, they were not filtered: {}",
+                error
+            ),
+            Self::Followers { error } => write!(f, "cannot fetch follower counts: {}", error),
+            Self::Watchlist { error } => write!(f, "cannot fetch the watchlist: {}", error),
+            Self::UnknownLogins { logins } => {
+                write!(f, "unknown channels: {}", logins.join(", "))
+            }
+            Self::Search { query, error } => {
+                write!(f, "cannot search channels for {}: {}", query, error)
+            }
+            Self::Count { category, error } => {
+                write!(f, "cannot count the streams in {}: {}", category, error)
+            }
+            Self::Schedule { login, error } => {
+                write!(f, "cannot fetch the schedule of {}: {}", login, error)
+            }
+            Self::Cache { name, error } => write!(f, "cannot write the {} cache: {}", name, error),
+        }
+    }
+}
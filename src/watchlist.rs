@@ -0,0 +1,111 @@
+use crate::{
+    helix::is_unauthorized, stream::resolve_labels, Client, Excluded, Filter, Stream, Warning,
+};
+
+/// Channels that are always shown, whatever they are streaming
+#[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Watchlist {
+    /// The logins of the channels
+    #[serde(default)]
+    pub logins: Vec<String>,
+    /// Also list the channels that aren't live
+    #[serde(default)]
+    pub show_offline: bool,
+}
+
+/// The channels on the watchlist, split by whether they are live
+#[derive(Debug, Default, serde::Serialize)]
+pub struct Watched {
+    pub live: Vec<Stream>,
+    /// The logins of the channels that aren't live
+    pub offline: Vec<Box<str>>,
+    /// The logins that don't belong to any user
+    pub unknown: Vec<Box<str>>,
+    /// The live streams that were hidden by the filter
+    #[serde(skip)]
+    pub excluded: Vec<Excluded>,
+    #[serde(skip)]
+    pub warnings: Vec<Warning>,
+}
+
+/// Fetches the streams of the channels on the watchlist
+///
+/// Only the content filters (see [`Filter::content`]) are used, the channels are shown whatever they are streaming.
+/// Failures after the streams were fetched are reported as [`Warning`]s
+pub fn fetch_watchlist(
+    client: &mut Client,
+    logins: &[String],
+    filter: &Filter,
+) -> anyhow::Result<Watched> {
+    let filter = filter.content();
+    client.retry_unauthorized(|client| try_fetch_watchlist(client, logins, &filter))
+}
+
+fn try_fetch_watchlist(
+    client: &Client,
+    logins: &[String],
+    filter: &Filter,
+) -> anyhow::Result<Watched> {
+    let mut watched = Watched::default();
+
+    for logins in logins.chunks(100) {
+        // look up the users first, so the logins that don't exist can be told apart from the offline ones
+        let users = crate::get_users(client, "login", logins.iter().map(|s| &**s))?;
+
+        let query = std::iter::repeat("user_id")
+            .zip(users.keys().map(|s| &**s))
+            .chain([("first", "100")])
+            .collect::<Vec<_>>();
+
+        let mut streams = if users.is_empty() {
+            vec![]
+        } else {
+            client.get_data::<Stream>("streams", &query)?
+        };
+
+        for stream in &mut streams {
+            if let Some(user) = users.get(&stream.user_id) {
+                stream.set_user(user);
+            }
+            stream.fix_uptime();
+        }
+
+        if !filter.hide_labels.is_empty() {
+            match resolve_labels(client, &mut streams) {
+                Err(err) if is_unauthorized(&err) => return Err(err),
+                Err(err) => watched.warnings.push(Warning::labels(&err)),
+                Ok(()) => {}
+            }
+        }
+
+        for login in logins {
+            let user = users
+                .values()
+                .find(|user| user.login.eq_ignore_ascii_case(login));
+
+            match user {
+                Some(user) if !streams.iter().any(|s| s.user_id == user.id) => {
+                    watched.offline.push(user.login.clone())
+                }
+                Some(..) => {}
+                None => watched.unknown.push(login.as_str().into()),
+            }
+        }
+
+        for stream in streams {
+            let reasons = filter.reasons(&stream);
+            if reasons.is_empty() {
+                watched.live.push(stream);
+                continue;
+            }
+            watched.excluded.push(Excluded {
+                query: "watchlist".to_string(),
+                user_name: stream.user_name,
+                user_id: stream.user_id,
+                reasons,
+            });
+        }
+    }
+
+    Ok(watched)
+}